@@ -0,0 +1,22 @@
+#![no_main]
+
+use filler_visualizer::parse::State;
+use libfuzzer_sys::fuzz_target;
+
+// anything the engine or a player prints goes through the parser, it has to come out
+// as turns or errors and never as a panic
+fuzz_target!(|data: &[u8]| {
+    let text = String::from_utf8_lossy(data);
+    let mut state = State::default();
+
+    for line in text.lines() {
+        let _ = state.parse_line(line, false);
+    }
+    state.end_of_stream(false);
+
+    for turn in &state.turns {
+        assert_eq!(turn.map.tiles.len(), turn.map.heigth);
+        assert_eq!(turn.piece.tiles.len(), turn.piece.heigth);
+    }
+    let _ = state.final_map();
+});
@@ -0,0 +1,300 @@
+use bevy::prelude::{Color, Commands, Component, Entity, Query, Res, ResMut, With};
+use bevy_egui::{
+    egui::{
+        self,
+        plot::{Legend, Line, LineStyle, Plot, PlotPoints, VLine},
+        Color32,
+    },
+    EguiContext,
+};
+
+use crate::{
+    draw::Panels,
+    games::Focused,
+    overlay::{ColorRamp, Layer},
+    parse::{
+        map::{Map, MapTile},
+        State,
+    },
+    playback::Playback,
+    Args,
+};
+
+use super::distances;
+
+/// Who gets to an empty cell first, walking over empty cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reach {
+    Player1,
+    Player2,
+    /// Both players are the same number of steps away
+    Contested,
+    /// Walled off from both players
+    Nobody,
+}
+
+/// How much room both players have left on a board
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Mobility {
+    /// Empty cells each player can still get to
+    pub reachable: [usize; 2],
+    /// Empty cells each player gets to before the other
+    pub territory: [usize; 2],
+    pub contested: usize,
+}
+
+impl Mobility {
+    /// Where the counts of the player are kept
+    pub fn index(tile: MapTile) -> usize {
+        match tile {
+            MapTile::Player2 => 1,
+            _ => 0,
+        }
+    }
+}
+
+/// Who gets to a cell first, from how many steps both players are away from it
+fn reach(player1: Option<usize>, player2: Option<usize>) -> Reach {
+    match (player1, player2) {
+        (Some(a), Some(b)) if a < b => Reach::Player1,
+        (Some(a), Some(b)) if a > b => Reach::Player2,
+        (Some(_), Some(_)) => Reach::Contested,
+        (Some(_), None) => Reach::Player1,
+        (None, Some(_)) => Reach::Player2,
+        (None, None) => Reach::Nobody,
+    }
+}
+
+/// Steps both players are away from every empty cell, by `y * width + x`, None for tiles
+/// that aren't empty
+fn empty_distances(map: &Map) -> Vec<Option<(Option<usize>, Option<usize>)>> {
+    let player1 = distances(map, MapTile::Player1);
+    let player2 = distances(map, MapTile::Player2);
+
+    map.tiles
+        .iter()
+        .flatten()
+        .zip(player1.into_iter().zip(player2))
+        .map(|(tile, distances)| (*tile == MapTile::Empty).then_some(distances))
+        .collect()
+}
+
+/// Splits the empty cells of the board by which player is fewer steps away, by `y * width + x`,
+/// None for tiles that aren't empty
+pub fn voronoi(map: &Map) -> Vec<Option<Reach>> {
+    empty_distances(map)
+        .into_iter()
+        .map(|distances| distances.map(|(a, b)| reach(a, b)))
+        .collect()
+}
+
+pub fn mobility(map: &Map) -> Mobility {
+    let mut mobility = Mobility::default();
+
+    for (a, b) in empty_distances(map).into_iter().flatten() {
+        mobility.reachable[0] += a.is_some() as usize;
+        mobility.reachable[1] += b.is_some() as usize;
+
+        match reach(a, b) {
+            Reach::Player1 => mobility.territory[0] += 1,
+            Reach::Player2 => mobility.territory[1] += 1,
+            Reach::Contested => mobility.contested += 1,
+            Reach::Nobody => {}
+        }
+    }
+
+    mobility
+}
+
+/// Mobility of every board of a game, by board like `State::board`
+#[derive(Component, Debug, Default)]
+pub struct MobilityAnalysis {
+    pub boards: Vec<Mobility>,
+}
+
+/// Keeps the mobility of every board up to date, new boards are added as they are parsed
+pub fn analyse_mobility(
+    mut commands: Commands,
+    mut games: Query<(Entity, &State, Option<&mut MobilityAnalysis>)>,
+) {
+    for (entity, state, analysis) in games.iter_mut() {
+        let Some(mut analysis) = analysis else {
+            commands.entity(entity).insert(MobilityAnalysis::default());
+            continue;
+        };
+
+        // the final board only shows up once the game finished
+        while let Some(map) = state.board(analysis.boards.len()) {
+            analysis.boards.push(mobility(map));
+        }
+    }
+}
+
+/// Empty cells tinted with the player that reaches them first, yellow where both are as close
+pub struct ReachLayer;
+
+impl Layer for ReachLayer {
+    fn name(&self) -> &'static str {
+        "Reach"
+    }
+
+    fn description(&self) -> &'static str {
+        "Which player gets to each empty cell first, walking over empty cells"
+    }
+
+    fn values(&self, state: &State, board: usize) -> Vec<Option<f32>> {
+        let Some(map) = state.board(board) else {
+            return Vec::new();
+        };
+
+        voronoi(map)
+            .into_iter()
+            .map(|reach| match reach? {
+                Reach::Player1 => Some(-1.0),
+                Reach::Contested => Some(0.0),
+                Reach::Player2 => Some(1.0),
+                Reach::Nobody => None,
+            })
+            .collect()
+    }
+
+    fn ramp(&self, args: &Args) -> ColorRamp {
+        ColorRamp(vec![
+            args.player1_color.to_color(),
+            Color::YELLOW,
+            args.player2_color.to_color(),
+        ])
+    }
+
+    fn range(&self, _: &[Option<f32>]) -> (f32, f32) {
+        (-1.0, 1.0)
+    }
+}
+
+/// Room left for both players on the shown board and over the whole game
+pub fn draw_mobility(
+    panels: Res<Panels>,
+    args: Res<Args>,
+    mut egui_context: ResMut<EguiContext>,
+
+    games: Query<(&Playback, &MobilityAnalysis), With<Focused>>,
+) {
+    if !panels.shown {
+        return;
+    }
+    let Ok((playback, analysis)) = games.get_single() else {
+        return;
+    };
+
+    egui::Window::new("Mobility").show(egui_context.ctx_mut(), |ui| {
+        if let Some(mobility) = analysis.boards.get(playback.turn) {
+            for player in 0..2 {
+                ui.label(format!(
+                    "Player {}: reaches {}, first to {}",
+                    player + 1,
+                    mobility.reachable[player],
+                    mobility.territory[player]
+                ));
+            }
+            ui.label(format!("Contested: {}", mobility.contested));
+        }
+
+        Plot::new("mobility")
+            .height(200.0)
+            .legend(Legend::default())
+            .show(ui, |plot| {
+                let colors = [args.player1_color, args.player2_color]
+                    .map(|rgb| Color32::from_rgb(rgb.0, rgb.1, rgb.2));
+
+                for (player, color) in colors.into_iter().enumerate() {
+                    let curve = |value: fn(&Mobility) -> [usize; 2]| -> PlotPoints {
+                        analysis
+                            .boards
+                            .iter()
+                            .enumerate()
+                            .map(|(i, mobility)| [i as f64, value(mobility)[player] as f64])
+                            .collect()
+                    };
+
+                    plot.line(
+                        Line::new(curve(|m| m.reachable))
+                            .color(color)
+                            .name(format!("player {} reachable", player + 1)),
+                    );
+                    plot.line(
+                        Line::new(curve(|m| m.territory))
+                            .color(color)
+                            .style(LineStyle::dashed_loose())
+                            .name(format!("player {} territory", player + 1)),
+                    );
+                }
+
+                plot.vline(VLine::new(playback.turn as f64).color(Color32::GRAY));
+            });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::tests::map;
+
+    use super::*;
+
+    #[test]
+    fn voronoi_splits_by_distance() {
+        // the middle cell is as far from both players
+        let map = map(&["@...$"]);
+
+        assert_eq!(
+            voronoi(&map),
+            vec![
+                None,
+                Some(Reach::Player1),
+                Some(Reach::Contested),
+                Some(Reach::Player2),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn walled_off_cells_belong_to_nobody() {
+        // the right half is cut off by tiles that are neither empty nor either player's
+        let mut map = map(&["@....", ".....", "$...."]);
+        for (x, y) in [(2, 0), (2, 1), (1, 2), (2, 2)] {
+            map.tiles[y][x] = MapTile::None;
+        }
+
+        let reach = voronoi(&map);
+        assert_eq!(reach[1], Some(Reach::Player1));
+        assert_eq!(reach[5], Some(Reach::Contested));
+        assert_eq!(reach[6], Some(Reach::Contested));
+        for i in [3, 4, 8, 9, 13, 14] {
+            assert_eq!(reach[i], Some(Reach::Nobody), "cell {i}");
+        }
+
+        assert_eq!(
+            mobility(&map),
+            Mobility {
+                reachable: [3, 3],
+                territory: [1, 0],
+                contested: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn mobility_counts_match_voronoi() {
+        let map = map(&["@..", "...", "..$"]);
+        let reach = voronoi(&map);
+        let count = |r| reach.iter().filter(|c| **c == Some(r)).count();
+
+        let mobility = mobility(&map);
+        assert_eq!(
+            mobility.territory,
+            [count(Reach::Player1), count(Reach::Player2)]
+        );
+        assert_eq!(mobility.contested, count(Reach::Contested));
+        assert_eq!(mobility.reachable, [7, 7]);
+    }
+}
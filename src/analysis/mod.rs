@@ -0,0 +1,178 @@
+use std::collections::VecDeque;
+
+use crate::parse::{
+    map::{Map, MapTile},
+    piece::Piece,
+};
+
+pub mod mobility;
+pub mod moves;
+
+/// The other player
+pub fn opponent(tile: MapTile) -> MapTile {
+    match tile {
+        MapTile::Player1 => MapTile::Player2,
+        MapTile::Player2 => MapTile::Player1,
+        other => other,
+    }
+}
+
+/// Every answer the engine would accept, the piece covers exactly one tile of the player
+/// and none of the opponent
+pub fn placements(map: &Map, piece: &Piece, tile: MapTile) -> Vec<(usize, usize)> {
+    (0..map.heigth)
+        .flat_map(|y| (0..map.width).map(move |x| (x, y)))
+        .filter(|pos| map.fits(piece, *pos, tile))
+        .collect()
+}
+
+/// Steps every cell is away from the tiles of the player, walking over empty cells only.
+/// Indexed by `y * width + x`, None for cells the player can't reach
+pub fn distances(map: &Map, tile: MapTile) -> Vec<Option<usize>> {
+    let mut distances = vec![None; map.width * map.heigth];
+    let mut queue = VecDeque::new();
+
+    for (y, row) in map.tiles.iter().enumerate() {
+        for (x, t) in row.iter().enumerate() {
+            if *t == tile {
+                distances[y * map.width + x] = Some(0);
+                queue.push_back((x, y));
+            }
+        }
+    }
+
+    while let Some((x, y)) = queue.pop_front() {
+        let next = distances[y * map.width + x].unwrap() + 1;
+
+        for (nx, ny) in neighbours(map, x, y) {
+            let i = ny * map.width + nx;
+            if distances[i].is_none() && map.tiles[ny][nx] == MapTile::Empty {
+                distances[i] = Some(next);
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+
+    distances
+}
+
+/// The up to four cells next to (x, y)
+pub fn neighbours(map: &Map, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+    let (width, heigth) = (map.width, map.heigth);
+
+    [(0, -1), (-1, 0), (1, 0), (0, 1)]
+        .into_iter()
+        .filter_map(move |(dx, dy)| {
+            let nx = x.checked_add_signed(dx)?;
+            let ny = y.checked_add_signed(dy)?;
+            (nx < width && ny < heigth).then_some((nx, ny))
+        })
+}
+
+/// Empty cells touching a tile of the player
+pub fn frontier(map: &Map, tile: MapTile) -> usize {
+    (0..map.heigth)
+        .flat_map(|y| (0..map.width).map(move |x| (x, y)))
+        .filter(|(x, y)| {
+            map.tiles[*y][*x] == MapTile::Empty
+                && neighbours(map, *x, *y).any(|(nx, ny)| map.tiles[ny][nx] == tile)
+        })
+        .count()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::parse::piece::PieceTile;
+
+    use super::*;
+
+    pub(crate) fn map(rows: &[&str]) -> Map {
+        rows.join("\n").parse().unwrap()
+    }
+
+    pub(crate) fn piece(rows: &[&str]) -> Piece {
+        Piece {
+            start: 0,
+            width: rows[0].len(),
+            heigth: rows.len(),
+            tiles: rows
+                .iter()
+                .map(|row| row.chars().map(PieceTile::from_char).collect())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn placements_cover_exactly_one_own_tile() {
+        let map = map(&["@@..", "....", "...$"]);
+
+        // (0, 0) covers both tiles of player 1, (2, 0) none of them
+        assert_eq!(
+            placements(&map, &piece(&["OO"]), MapTile::Player1),
+            vec![(1, 0)]
+        );
+        assert_eq!(
+            placements(&map, &piece(&["O", "O"]), MapTile::Player1),
+            vec![(0, 0), (1, 0)]
+        );
+    }
+
+    #[test]
+    fn placements_never_cover_the_opponent() {
+        let map = map(&["@$", ".."]);
+
+        assert!(placements(&map, &piece(&["OO"]), MapTile::Player1).is_empty());
+        assert_eq!(
+            placements(&map, &piece(&["O", "O"]), MapTile::Player1),
+            vec![(0, 0)]
+        );
+    }
+
+    #[test]
+    fn placements_stay_on_the_board() {
+        let map = map(&["..@"]);
+
+        assert!(placements(&map, &piece(&["OO"]), MapTile::Player1).contains(&(1, 0)));
+        assert!(!placements(&map, &piece(&["OO"]), MapTile::Player1).contains(&(2, 0)));
+    }
+
+    #[test]
+    fn distances_walk_over_empty_cells() {
+        let map = map(&["@..$.", "....."]);
+
+        assert_eq!(
+            distances(&map, MapTile::Player1),
+            vec![
+                Some(0),
+                Some(1),
+                Some(2),
+                None,
+                // around the opponent through the row below
+                Some(6),
+                Some(1),
+                Some(2),
+                Some(3),
+                Some(4),
+                Some(5),
+            ]
+        );
+    }
+
+    #[test]
+    fn distances_stop_at_walls() {
+        let map = map(&["@.$."]);
+
+        assert_eq!(
+            distances(&map, MapTile::Player1),
+            vec![Some(0), Some(1), None, None]
+        );
+    }
+
+    #[test]
+    fn frontier_counts_empty_neighbours() {
+        let map = map(&["@..", ".$.", "..."]);
+
+        assert_eq!(frontier(&map, MapTile::Player1), 2);
+        assert_eq!(frontier(&map, MapTile::Player2), 4);
+    }
+}
@@ -0,0 +1,404 @@
+use std::time::{Duration, Instant};
+
+use bevy::prelude::{Commands, Component, Entity, Query, Res, ResMut, Resource, With};
+use bevy_egui::{egui, EguiContext};
+
+use crate::{
+    draw::Panels,
+    games::Focused,
+    parse::{map::MapTile, piece::PieceTile, turn::Turn, State},
+    playback::Playback,
+};
+
+use super::{
+    distances, frontier,
+    mobility::{mobility, Mobility},
+    opponent, placements,
+};
+
+/// Time spent ranking answers every frame, the rest of the game is ranked on later frames
+const BUDGET: Duration = Duration::from_millis(5);
+
+/// How much each heuristic counts towards the score of a placement
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct Heuristics {
+    /// Per tile the piece lands closer to the opponent
+    pub distance: f32,
+    /// Per empty cell the player reaches before the opponent
+    pub territory: f32,
+    /// Per empty cell next to the opponent the piece covers
+    pub mobility: f32,
+    /// Per empty cell the opponent can't reach anymore
+    pub enclosure: f32,
+
+    /// Score a better placement needs over the answer to make it a blunder
+    pub blunder_margin: f32,
+}
+
+impl Default for Heuristics {
+    fn default() -> Self {
+        Heuristics {
+            distance: 1.0,
+            territory: 1.0,
+            mobility: 2.0,
+            enclosure: 1.0,
+            blunder_margin: 10.0,
+        }
+    }
+}
+
+/// Where the answer of a turn ranked among every legal placement of its piece
+#[derive(Debug, Clone)]
+pub struct MoveRank {
+    /// 1 for the best placement, None if the answer wasn't a legal placement
+    pub rank: Option<usize>,
+    pub candidates: usize,
+    pub score: f32,
+
+    pub best: (usize, usize),
+    pub best_score: f32,
+}
+
+impl MoveRank {
+    /// Score lost against the best placement
+    pub fn loss(&self) -> f32 {
+        self.best_score - self.score
+    }
+
+    pub fn is_blunder(&self, heuristics: &Heuristics) -> bool {
+        self.rank.is_some() && self.loss() >= heuristics.blunder_margin
+    }
+}
+
+/// Ranks of the answers of a game, filled in a few placements every frame
+#[derive(Component, Debug)]
+pub struct MoveAnalysis {
+    /// Heuristics the ranks were worked out with, the game is ranked again when they change
+    heuristics: Heuristics,
+    /// By turn, None where there was no answer to rank
+    pub ranks: Vec<Option<MoveRank>>,
+    /// The turn after the ranked ones, when a frame ran out of time halfway through it
+    pending: Option<Ranking>,
+}
+
+/// Ranking of one turn, kept between frames so a big board can be scored over several
+#[derive(Debug)]
+pub struct Ranking {
+    answer: (usize, usize),
+    tile: MapTile,
+    candidates: Vec<(usize, usize)>,
+    /// Of the candidates scored so far, in the same order
+    scores: Vec<f32>,
+
+    // everything about the board before the answer is worked out once for all placements
+    to_opponent: Vec<Option<usize>>,
+    mobility_before: Mobility,
+    frontier_before: f32,
+}
+
+impl Ranking {
+    /// None when there is no answer or nowhere to place the piece
+    pub fn new(turn: &Turn) -> Option<Ranking> {
+        let (answer, tile) = (turn.answer.answer?, turn.answer.tile()?);
+        let map = &turn.map;
+        let other = opponent(tile);
+
+        let candidates = placements(map, &turn.piece, tile);
+        if candidates.is_empty() {
+            return None;
+        }
+
+        Some(Ranking {
+            answer,
+            tile,
+            scores: Vec::with_capacity(candidates.len()),
+            candidates,
+            to_opponent: distances(map, other),
+            mobility_before: mobility(map),
+            frontier_before: frontier(map, other) as f32,
+        })
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.scores.len() == self.candidates.len()
+    }
+
+    /// Scores the next placement, the turn has to be the one the ranking was made for
+    pub fn step(&mut self, turn: &Turn, heuristics: &Heuristics) {
+        let Some(&(x, y)) = self.candidates.get(self.scores.len()) else {
+            return;
+        };
+        let (map, tile) = (&turn.map, self.tile);
+        let other = opponent(tile);
+        let far = (map.width + map.heigth) as f32;
+
+        let after = map.with_piece(&turn.piece, (x, y), tile);
+        let (own, opponent) = (Mobility::index(tile), Mobility::index(other));
+        let (before, mobility) = (&self.mobility_before, mobility(&after));
+
+        let closest = turn
+            .piece
+            .tiles
+            .iter()
+            .enumerate()
+            .flat_map(|(py, row)| row.iter().enumerate().map(move |(px, t)| (px, py, t)))
+            .filter(|(_, _, t)| **t == PieceTile::Piece)
+            .filter_map(|(px, py, _)| self.to_opponent[(y + py) * map.width + x + px])
+            .min()
+            .map_or(far, |d| d as f32);
+
+        let territory = mobility.territory[own] as f32 - before.territory[own] as f32;
+        let enclosure = before.reachable[opponent] as f32 - mobility.reachable[opponent] as f32;
+
+        self.scores.push(
+            heuristics.distance * (far - closest)
+                + heuristics.territory * territory
+                + heuristics.mobility * (self.frontier_before - frontier(&after, other) as f32)
+                + heuristics.enclosure * enclosure,
+        );
+    }
+
+    /// Where the answer ranked, once every placement is scored
+    pub fn finish(&self) -> MoveRank {
+        let scores: Vec<_> = self
+            .candidates
+            .iter()
+            .copied()
+            .zip(self.scores.iter().copied())
+            .collect();
+        let (best, best_score) = scores
+            .iter()
+            .copied()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap();
+
+        let Some(score) = scores
+            .iter()
+            .find(|(c, _)| *c == self.answer)
+            .map(|(_, s)| *s)
+        else {
+            return MoveRank {
+                rank: None,
+                candidates: scores.len(),
+                score: f32::MIN,
+                best,
+                best_score,
+            };
+        };
+
+        MoveRank {
+            rank: Some(1 + scores.iter().filter(|(_, s)| *s > score).count()),
+            candidates: scores.len(),
+            score,
+            best,
+            best_score,
+        }
+    }
+}
+
+/// Scores every legal placement of the piece and ranks the answer among them
+pub fn rank(turn: &Turn, heuristics: &Heuristics) -> Option<MoveRank> {
+    let mut ranking = Ranking::new(turn)?;
+    while !ranking.is_done() {
+        ranking.step(turn, heuristics);
+    }
+
+    Some(ranking.finish())
+}
+
+/// Ranks the answers of every game in the background, starting over when the heuristics change.
+/// The time is checked after every placement, one turn of a big board can take several frames
+pub fn analyse_moves(
+    mut commands: Commands,
+    heuristics: Res<Heuristics>,
+    mut games: Query<(Entity, &State, Option<&mut MoveAnalysis>)>,
+) {
+    let start = Instant::now();
+
+    for (entity, state, analysis) in games.iter_mut() {
+        let Some(mut analysis) = analysis else {
+            commands.entity(entity).insert(MoveAnalysis {
+                heuristics: heuristics.clone(),
+                ranks: Vec::new(),
+                pending: None,
+            });
+            continue;
+        };
+
+        if analysis.heuristics != *heuristics {
+            analysis.heuristics = heuristics.clone();
+            analysis.ranks.clear();
+            analysis.pending = None;
+        }
+
+        while analysis.ranks.len() < state.turns.len() && start.elapsed() < BUDGET {
+            let turn = &state.turns[analysis.ranks.len()];
+            let Some(mut ranking) = analysis.pending.take().or_else(|| Ranking::new(turn)) else {
+                analysis.ranks.push(None);
+                continue;
+            };
+
+            while !ranking.is_done() && start.elapsed() < BUDGET {
+                ranking.step(turn, &heuristics);
+            }
+
+            if ranking.is_done() {
+                analysis.ranks.push(Some(ranking.finish()));
+            } else {
+                analysis.pending = Some(ranking);
+            }
+        }
+    }
+}
+
+/// Rank of the shown answer, the heuristics and every blunder of the focused game
+pub fn draw_analysis(
+    panels: Res<Panels>,
+    mut heuristics: ResMut<Heuristics>,
+    mut egui_context: ResMut<EguiContext>,
+
+    mut games: Query<(&State, &mut Playback, &MoveAnalysis), With<Focused>>,
+) {
+    if !panels.shown {
+        return;
+    }
+    let Ok((state, mut playback, analysis)) = games.get_single_mut() else {
+        return;
+    };
+
+    let count = state.board_count();
+
+    egui::Window::new("Analysis").show(egui_context.ctx_mut(), |ui| {
+        match analysis.ranks.get(playback.turn) {
+            Some(Some(rank)) => {
+                match rank.rank {
+                    Some(place) => ui.label(format!("Ranked {place} of {}", rank.candidates)),
+                    None => ui.colored_label(egui::Color32::RED, "Not a legal placement"),
+                };
+
+                let (x, y) = rank.best;
+                match rank.rank {
+                    Some(1) => {}
+                    Some(_) => {
+                        ui.label(format!("Best: {x} {y}, {:.1} better", rank.loss()));
+                    }
+                    None => {
+                        ui.label(format!("Best: {x} {y}"));
+                    }
+                }
+                if rank.is_blunder(&heuristics) {
+                    ui.colored_label(egui::Color32::RED, "Blunder");
+                }
+            }
+            Some(None) => {
+                ui.label("Nothing to rank");
+            }
+            None if playback.turn < state.turns.len() => {
+                ui.label("Analysing...");
+            }
+            None => {}
+        }
+
+        if analysis.ranks.len() < state.turns.len() {
+            ui.label(format!(
+                "Analysed {}/{} turns",
+                analysis.ranks.len(),
+                state.turns.len()
+            ));
+        }
+
+        ui.collapsing("Heuristics", |ui| {
+            let mut weights = heuristics.clone();
+            for (weight, text) in [
+                (&mut weights.distance, "distance to opponent"),
+                (&mut weights.territory, "territory gained"),
+                (&mut weights.mobility, "opponent mobility"),
+                (&mut weights.enclosure, "enclosure"),
+                (&mut weights.blunder_margin, "blunder margin"),
+            ] {
+                ui.add(egui::Slider::new(weight, 0.0..=20.0).text(text));
+            }
+
+            // only touched when a slider moved, so the ranks aren't thrown away every frame
+            if weights != *heuristics {
+                *heuristics = weights;
+            }
+        });
+
+        ui.separator();
+        ui.label("Blunders");
+        egui::ScrollArea::vertical()
+            .max_height(200.0)
+            .show(ui, |ui| {
+                for (i, rank) in analysis.ranks.iter().enumerate() {
+                    let Some(rank) = rank.as_ref().filter(|r| r.is_blunder(&heuristics)) else {
+                        continue;
+                    };
+
+                    let player = &state.turns[i].answer.player;
+                    let text = format!("{i}: ({player}) {:.1} worse than best", rank.loss());
+                    if ui.selectable_label(playback.turn == i, text).clicked() {
+                        playback.paused = true;
+                        playback.seek(i, count);
+                    }
+                }
+            });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        analysis::tests::{map, piece},
+        parse::answer::Answer,
+    };
+
+    use super::*;
+
+    fn turn(rows: &[&str], piece_rows: &[&str], answer: (usize, usize)) -> Turn {
+        Turn {
+            map: map(rows),
+            piece: piece(piece_rows),
+            answer: Answer {
+                player: "@".to_string(),
+                answer: Some(answer),
+            },
+        }
+    }
+
+    #[test]
+    fn rank_finds_the_best_move() {
+        // (2, 0) moves towards the opponent and walls off a cell it could still reach,
+        // (1, 0) only covers the corner player 1 had to itself already
+        let turn = turn(&["..@...$"], &["OO"], (1, 0));
+        let rank = rank(&turn, &Heuristics::default()).unwrap();
+
+        assert_eq!(rank.candidates, 2);
+        assert_eq!(rank.best, (2, 0));
+        assert_eq!(rank.rank, Some(2));
+        assert!(rank.loss() > 0.0);
+    }
+
+    #[test]
+    fn rank_of_the_best_answer_is_one() {
+        let turn = turn(&["..@...$"], &["OO"], (2, 0));
+        let rank = rank(&turn, &Heuristics::default()).unwrap();
+
+        assert_eq!(rank.rank, Some(1));
+        assert_eq!(rank.loss(), 0.0);
+    }
+
+    #[test]
+    fn illegal_answer_has_no_rank() {
+        let turn = turn(&["..@...$"], &["OO"], (4, 0));
+        let rank = rank(&turn, &Heuristics::default()).unwrap();
+
+        assert_eq!(rank.rank, None);
+        assert_eq!(rank.best, (2, 0));
+    }
+
+    #[test]
+    fn nothing_to_rank_without_placements() {
+        assert!(rank(&turn(&["@$"], &["OO"], (0, 0)), &Heuristics::default()).is_none());
+    }
+}
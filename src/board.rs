@@ -0,0 +1,398 @@
+use bevy::{
+    prelude::{Assets, Color, Component, Handle, Mesh, Query, Res, ResMut, Vec3},
+    render::{
+        mesh::{Indices, VertexAttributeValues},
+        render_resource::PrimitiveTopology,
+    },
+    time::Time,
+};
+use clap::ValueEnum;
+
+use crate::{
+    parse::{
+        claims::Claims,
+        diff::MapDiff,
+        map::{Map, MapTile},
+    },
+    Args,
+};
+
+const VERTICES_PER_CELL: usize = 24;
+
+/// How high above the board dropping tiles start
+const DROP_HEIGHT: f32 = 6.0;
+
+/// How far tiles claimed at the end of the claim scale rise above the first ones
+const MAX_EXTRUSION: f32 = 8.0;
+
+/// Normal and the two axes spanning each cube face, ordered so the face winds outwards
+const FACES: [([f32; 3], [f32; 3], [f32; 3]); 6] = [
+    ([1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]),
+    ([-1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]),
+    ([0.0, 1.0, 0.0], [0.0, 0.0, 1.0], [1.0, 0.0, 0.0]),
+    ([0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+    ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+    ([0.0, 0.0, -1.0], [0.0, 1.0, 0.0], [1.0, 0.0, 0.0]),
+];
+
+/// How newly claimed tiles appear on the board
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Animation {
+    /// Tiles change color instantly
+    None,
+    /// Tiles fall onto the board from above
+    Drop,
+    /// Tiles grow from nothing
+    Scale,
+    /// Tiles fade from the empty color to the player color
+    Fade,
+}
+
+/// What the board shows about when tiles were claimed
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClaimView {
+    /// Every tile looks the same
+    None,
+    /// Later tiles stand taller
+    Height,
+    /// Later tiles are lighter
+    Gradient,
+}
+
+/// Tile that is still animating in
+struct Placement {
+    x: usize,
+    y: usize,
+    tile: MapTile,
+    started: f64,
+}
+
+/// The whole map drawn as one mesh, every tile is a cube with its own vertex colors
+#[derive(Component)]
+pub struct Board {
+    pub width: usize,
+    pub heigth: usize,
+
+    /// Map the mesh currently shows
+    pub map: Map,
+    /// Board of the game the map came from, counted like `State::board`
+    pub turn: usize,
+
+    /// Turn every shown tile was claimed on
+    claimed: Vec<Option<usize>>,
+    placements: Vec<Placement>,
+}
+
+impl Board {
+    /// Builds the mesh for a map, the board is placed so tile (x, y) sits at world (x, 0, y)
+    pub fn new(map: &Map, claims: &Claims, args: &Args) -> (Board, Mesh) {
+        let cells = map.width * map.heigth;
+        let mut normals = Vec::with_capacity(cells * VERTICES_PER_CELL);
+        let mut indices = Vec::with_capacity(cells * 36);
+
+        for cell in 0..cells {
+            for (normal, _, _) in FACES {
+                normals.extend([normal; 4]);
+            }
+
+            let first = (cell * VERTICES_PER_CELL) as u32;
+            for face in 0..6 {
+                let f = first + face * 4;
+                indices.extend([f, f + 1, f + 2, f, f + 2, f + 3]);
+            }
+        }
+
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![[0.0; 3]; cells * VERTICES_PER_CELL],
+        );
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_COLOR,
+            vec![[0.0, 0.0, 0.0, 1.0]; cells * VERTICES_PER_CELL],
+        );
+        mesh.set_indices(Some(Indices::U32(indices)));
+
+        let mut board = Board {
+            width: map.width,
+            heigth: map.heigth,
+            map: Map {
+                tiles: vec![vec![MapTile::None; map.width]; map.heigth],
+                ..map.clone()
+            },
+            turn: 0,
+            claimed: vec![None; cells],
+            placements: Vec::new(),
+        };
+
+        // the first map is shown as is, there is nothing to animate from
+        board.update(&mut mesh, map, claims, args, None);
+        (board, mesh)
+    }
+
+    pub fn fits(&self, map: &Map) -> bool {
+        self.width == map.width && self.heigth == map.heigth
+    }
+
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+
+    /// Rewrites the tiles that differ from what the mesh shows, newly claimed tiles
+    /// start animating when `now` is given. Returns how many tiles changed
+    pub fn update(
+        &mut self,
+        mesh: &mut Mesh,
+        map: &Map,
+        claims: &Claims,
+        args: &Args,
+        now: Option<f64>,
+    ) -> usize {
+        // the previous placements haven't finished when turns come faster than the animation,
+        // they are snapped into place so the board never lags behind the game
+        self.finish_placements(mesh, args);
+
+        let diff = MapDiff::between(&self.map, map);
+        for change in &diff.changes {
+            if change.x >= self.width || change.y >= self.heigth {
+                continue;
+            }
+
+            let cell = self.index(change.x, change.y);
+            self.claimed[cell] = claims.get(change.x, change.y);
+
+            let placed = change.before == MapTile::Empty && change.after != MapTile::None;
+            match now {
+                Some(started) if placed && args.animation != Animation::None => {
+                    let placement = Placement {
+                        x: change.x,
+                        y: change.y,
+                        tile: change.after,
+                        started,
+                    };
+                    self.draw_placement(mesh, &placement, 0.0, args);
+                    self.placements.push(placement);
+                }
+                _ => self.draw_tile(mesh, change.x, change.y, change.after, args),
+            }
+        }
+
+        self.map = map.clone();
+        diff.changes.len()
+    }
+
+    /// Tile the ray hits first, tested against the boxes the tiles are drawn as
+    pub fn pick(&self, origin: Vec3, direction: Vec3, args: &Args) -> Option<(usize, usize)> {
+        let mut closest: Option<(f32, (usize, usize))> = None;
+
+        for (y, row) in self.map.tiles.iter().enumerate().take(self.heigth) {
+            for (x, tile) in row.iter().enumerate().take(self.width) {
+                if *tile == MapTile::None {
+                    continue;
+                }
+
+                let (center, half) = self.shape(x, y, *tile, args);
+                let Some(distance) = ray_box(origin, direction, center - half, center + half)
+                else {
+                    continue;
+                };
+
+                if closest.is_none_or(|(d, _)| distance < d) {
+                    closest = Some((distance, (x, y)));
+                }
+            }
+        }
+
+        closest.map(|(_, pos)| pos)
+    }
+
+    /// Moves the animating tiles along, finished ones are left in their final place
+    pub fn animate(&mut self, mesh: &mut Mesh, args: &Args, now: f64) {
+        let duration = args.animation_duration as f64 / 1000.0;
+        let placements = std::mem::take(&mut self.placements);
+
+        for placement in placements {
+            let progress = if duration > 0.0 {
+                ((now - placement.started) / duration) as f32
+            } else {
+                1.0
+            };
+
+            if progress >= 1.0 {
+                self.draw_tile(mesh, placement.x, placement.y, placement.tile, args);
+            } else {
+                self.draw_placement(mesh, &placement, progress, args);
+                self.placements.push(placement);
+            }
+        }
+    }
+
+    fn finish_placements(&mut self, mesh: &mut Mesh, args: &Args) {
+        for placement in std::mem::take(&mut self.placements) {
+            self.draw_tile(mesh, placement.x, placement.y, placement.tile, args);
+        }
+    }
+
+    /// How far along the claim scale the tile was claimed, from 0 to 1
+    fn age(&self, cell: usize, args: &Args) -> f32 {
+        let turn = self.claimed[cell].unwrap_or_default() as f32;
+        (turn / args.claim_scale.max(1) as f32).min(1.0)
+    }
+
+    /// Height of the top of the tile, above the empty tiles when claims are extruded
+    pub fn top(&self, x: usize, y: usize, args: &Args) -> f32 {
+        let tile = self.map.tiles.get(y).and_then(|r| r.get(x)).copied();
+        let (center, half) = self.shape(x, y, tile.unwrap_or_default(), args);
+        center.y + half.y
+    }
+
+    /// Center and half size of the box a tile is drawn as
+    fn shape(&self, x: usize, y: usize, tile: MapTile, args: &Args) -> (Vec3, Vec3) {
+        let center = Vec3::new(x as f32, 0.0, y as f32);
+
+        match tile {
+            MapTile::None => (Vec3::ZERO, Vec3::ZERO),
+            MapTile::Player1 | MapTile::Player2 if args.claim_view == ClaimView::Height => {
+                // the bottom stays level with the rest of the board and the top rises
+                let extrusion = self.age(self.index(x, y), args) * MAX_EXTRUSION;
+                (
+                    center + Vec3::Y * extrusion / 2.0,
+                    Vec3::new(0.5, 0.5 + extrusion / 2.0, 0.5),
+                )
+            }
+            _ => (center, Vec3::splat(0.5)),
+        }
+    }
+
+    fn color(&self, x: usize, y: usize, tile: MapTile, args: &Args) -> Color {
+        let color = tile_color(tile, args);
+
+        match tile {
+            MapTile::Player1 | MapTile::Player2 if args.claim_view == ClaimView::Gradient => {
+                // goes from a dark shade through the player color to a light shade
+                let age = self.age(self.index(x, y), args);
+                if age < 0.5 {
+                    mix(mix(Color::BLACK, color, 0.4), color, age * 2.0)
+                } else {
+                    mix(color, Color::WHITE, (age - 0.5) * 1.2)
+                }
+            }
+            _ => color,
+        }
+    }
+
+    fn draw_tile(&self, mesh: &mut Mesh, x: usize, y: usize, tile: MapTile, args: &Args) {
+        let cell = self.index(x, y);
+        let (center, half) = self.shape(x, y, tile, args);
+
+        write_cube(mesh, cell, center, half);
+        write_color(mesh, cell, self.color(x, y, tile, args));
+    }
+
+    fn draw_placement(&self, mesh: &mut Mesh, placement: &Placement, progress: f32, args: &Args) {
+        let (x, y, tile) = (placement.x, placement.y, placement.tile);
+        let cell = self.index(x, y);
+        let (center, half) = self.shape(x, y, tile, args);
+
+        // eases out so tiles slow down as they land
+        let eased = 1.0 - (1.0 - progress).powi(3);
+        let color = self.color(x, y, tile, args);
+
+        match args.animation {
+            Animation::Drop => {
+                let lifted = center + Vec3::Y * DROP_HEIGHT * (1.0 - eased);
+                write_cube(mesh, cell, lifted, half);
+                write_color(mesh, cell, color);
+            }
+            Animation::Scale => {
+                write_cube(mesh, cell, center, half * eased);
+                write_color(mesh, cell, color);
+            }
+            Animation::Fade => {
+                write_cube(mesh, cell, center, half);
+                write_color(
+                    mesh,
+                    cell,
+                    mix(tile_color(MapTile::Empty, args), color, eased),
+                );
+            }
+            Animation::None => self.draw_tile(mesh, x, y, tile, args),
+        }
+    }
+}
+
+pub fn animate_board(
+    time: Res<Time>,
+    args: Res<Args>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut boards: Query<(&mut Board, &Handle<Mesh>)>,
+) {
+    for (mut board, handle) in boards.iter_mut() {
+        if board.placements.is_empty() {
+            continue;
+        }
+
+        if let Some(mesh) = meshes.get_mut(handle) {
+            board.animate(mesh, &args, time.elapsed_seconds_f64());
+        }
+    }
+}
+
+pub fn tile_color(tile: MapTile, args: &Args) -> Color {
+    match tile {
+        MapTile::Player1 => args.player1_color.to_color(),
+        MapTile::Player2 => args.player2_color.to_color(),
+        _ => Color::WHITE,
+    }
+}
+
+/// Distance along the ray to where it enters the box, using the slab method
+fn ray_box(origin: Vec3, direction: Vec3, min: Vec3, max: Vec3) -> Option<f32> {
+    let inverse = direction.recip();
+    let (t1, t2) = ((min - origin) * inverse, (max - origin) * inverse);
+
+    let near = t1.min(t2).max_element().max(0.0);
+    let far = t1.max(t2).min_element();
+
+    (far >= near).then_some(near)
+}
+
+/// Linear blend between two colors, `amount` 0 gives `from` and 1 gives `to`
+fn mix(from: Color, to: Color, amount: f32) -> Color {
+    let (from, to) = (from.as_linear_rgba_f32(), to.as_linear_rgba_f32());
+    let [r, g, b, a]: [f32; 4] = std::array::from_fn(|i| from[i] + (to[i] - from[i]) * amount);
+
+    Color::rgba_linear(r, g, b, a)
+}
+
+fn write_color(mesh: &mut Mesh, cell: usize, color: Color) {
+    if let Some(VertexAttributeValues::Float32x4(colors)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_COLOR)
+    {
+        colors[cell * VERTICES_PER_CELL..(cell + 1) * VERTICES_PER_CELL]
+            .fill(color.as_linear_rgba_f32());
+    }
+}
+
+/// Places the 24 vertices of one cell as a box around the center
+fn write_cube(mesh: &mut Mesh, cell: usize, center: Vec3, half: Vec3) {
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return;
+    };
+    let vertices = &mut positions[cell * VERTICES_PER_CELL..(cell + 1) * VERTICES_PER_CELL];
+
+    for (face, (normal, u, v)) in FACES.iter().enumerate() {
+        let (n, u, v) = (Vec3::from(*normal), Vec3::from(*u), Vec3::from(*v));
+
+        for (corner, (su, sv)) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+            .into_iter()
+            .enumerate()
+        {
+            let offset = n + u * su + v * sv;
+            vertices[face * 4 + corner] = (center + offset * half).to_array();
+        }
+    }
+}
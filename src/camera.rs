@@ -0,0 +1,96 @@
+use bevy::{
+    prelude::{
+        Camera, Camera3dBundle, Commands, Component, GlobalTransform, Query, Res, Resource,
+        Transform, Vec3, With, Without,
+    },
+    render::camera::{PerspectiveProjection, Projection},
+};
+use bevy_flycam::FlyCam;
+
+use crate::{
+    board::Board,
+    games::{Focused, GameView},
+};
+
+/// Which camera renders the board
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraMode {
+    /// The flycam, moved around with the mouse and keyboard
+    #[default]
+    Fly,
+    /// Looks straight down at the whole board, or every board in the grid, row 0 at the top like the engine prints it
+    TopDown,
+}
+
+impl CameraMode {
+    pub fn switch(&mut self) {
+        *self = match self {
+            CameraMode::Fly => CameraMode::TopDown,
+            CameraMode::TopDown => CameraMode::Fly,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct TopDownCamera;
+
+pub fn spawn_top_down_camera(mut commands: Commands) {
+    commands.spawn((
+        Camera3dBundle {
+            camera: Camera {
+                is_active: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        TopDownCamera,
+    ));
+}
+
+type TopDownQuery<'a> = (&'a mut Camera, &'a mut Transform, &'a Projection);
+
+pub fn switch_camera(
+    mode: Res<CameraMode>,
+    view: Res<GameView>,
+    boards: Query<(&Board, &GlobalTransform, Option<&Focused>)>,
+
+    mut fly_cameras: Query<&mut Camera, (With<FlyCam>, Without<TopDownCamera>)>,
+    mut top_down_cameras: Query<TopDownQuery, (With<TopDownCamera>, Without<FlyCam>)>,
+) {
+    for mut camera in fly_cameras.iter_mut() {
+        camera.is_active = *mode == CameraMode::Fly;
+    }
+
+    // the focused board in tabs, every board in the grid
+    let (min, max) = boards
+        .iter()
+        .filter(|(_, _, focused)| *view == GameView::Grid || focused.is_some())
+        .map(|(board, transform, _)| {
+            let corner = transform.translation();
+            let size = Vec3::new(board.width as f32 - 1.0, 0.0, board.heigth as f32 - 1.0);
+            (corner, corner + size)
+        })
+        .fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(min, max), (a, b)| (min.min(a), max.max(b)),
+        );
+
+    for (mut camera, mut transform, projection) in top_down_cameras.iter_mut() {
+        camera.is_active = *mode == CameraMode::TopDown;
+
+        let Projection::Perspective(PerspectiveProjection { fov, .. }) = projection else {
+            continue;
+        };
+        if min.x > max.x {
+            continue;
+        }
+
+        // backs off until the longer side of the boards fits the view
+        let center = (min + max) / 2.0;
+        let size = (max.x - min.x).max(max.z - min.z) + 3.0;
+        let height = size / 2.0 / (fov / 2.0).tan();
+
+        *transform =
+            Transform::from_translation(center + Vec3::Y * height).looking_at(center, Vec3::NEG_Z);
+    }
+}
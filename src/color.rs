@@ -0,0 +1,45 @@
+use std::{fmt::Display, str::FromStr};
+
+use bevy::prelude::Color;
+
+/// Color given on the command line as `#rrggbb`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+
+    pub fn to_color(self) -> Color {
+        Color::rgb_u8(self.0, self.1, self.2)
+    }
+
+    /// Mixes the color towards white, 0 keeps it and 1 is fully white
+    pub fn lighten(self, amount: f32) -> Rgb {
+        let mix = |c: u8| (c as f32 + (255.0 - c as f32) * amount).round() as u8;
+        Rgb(mix(self.0), mix(self.1), mix(self.2))
+    }
+}
+
+impl FromStr for Rgb {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.trim_start_matches('#');
+        if hex.len() != 6 || !hex.is_ascii() {
+            return Err(format!("expected a color like #ff0000, got {s:?}"));
+        }
+
+        let channel = |i: usize| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map_err(|_| format!("expected a color like #ff0000, got {s:?}"))
+        };
+
+        Ok(Rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+impl Display for Rgb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
@@ -0,0 +1,292 @@
+use std::{mem, path::PathBuf};
+
+use bevy::prelude::{Entity, Query, Res, ResMut, Resource, With};
+use bevy_egui::{
+    egui::{
+        self,
+        plot::{Legend, Line, LineStyle, Plot, PlotPoints, Points, VLine},
+        Color32,
+    },
+    EguiContext,
+};
+use clap::Args as ClapArgs;
+
+use crate::{
+    color::Rgb,
+    draw::Panels,
+    games::Focused,
+    parse::{map::MapTile, State},
+    playback::Playback,
+    Args,
+};
+
+#[derive(ClapArgs, Clone, Debug)]
+pub struct CompareArgs {
+    /// Game played before the change, engine output or json replay
+    pub before: PathBuf,
+
+    /// The same map and seed played after the change
+    pub after: PathBuf,
+}
+
+/// Two games of the same map and seed shown side by side with a shared turn
+#[derive(Resource, Debug)]
+pub struct Comparison {
+    /// The game before and after the change
+    pub games: [Entity; 2],
+
+    /// Turns where both games had the same board and piece but answered differently
+    pub diverged: Vec<usize>,
+    /// First board that isn't the same in both games
+    pub boards_differ: Option<usize>,
+    /// Whether both games started on the same board with the same seed
+    pub same_start: bool,
+
+    /// Tiles of player 1 and 2 on every board, for the score curves
+    scores: [Vec<(usize, usize)>; 2],
+}
+
+impl Comparison {
+    pub fn new(games: [Entity; 2], before: &State, after: &State) -> Self {
+        // the line numbers the boards were parsed on differ, so only the tiles are compared
+        let diverged = before
+            .turns
+            .iter()
+            .zip(&after.turns)
+            .enumerate()
+            .filter(|(_, (a, b))| {
+                a.map.tiles == b.map.tiles
+                    && a.piece.tiles == b.piece.tiles
+                    && a.answer.answer != b.answer.answer
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        let count = before.board_count().max(after.board_count());
+        let boards_differ = (0..count).find(|i| match (before.board(*i), after.board(*i)) {
+            (Some(a), Some(b)) => a.tiles != b.tiles,
+            _ => true,
+        });
+
+        // a missing seed says nothing about the games being the same
+        let same_start = !before.win.seed.is_empty()
+            && before.win.seed == after.win.seed
+            && before.turns.first().map(|t| &t.map.tiles)
+                == after.turns.first().map(|t| &t.map.tiles);
+
+        Comparison {
+            games,
+            diverged,
+            boards_differ,
+            same_start,
+            scores: [scores(before), scores(after)],
+        }
+    }
+}
+
+fn scores(state: &State) -> Vec<(usize, usize)> {
+    (0..state.board_count())
+        .filter_map(|i| state.board(i))
+        .map(|map| (map.count(MapTile::Player1), map.count(MapTile::Player2)))
+        .collect()
+}
+
+/// The focused game leads, the other one follows its turn and speed
+pub fn sync_playback(
+    comparison: Option<Res<Comparison>>,
+    mut games: Query<(&State, &mut Playback, Option<&Focused>)>,
+) {
+    let Some(comparison) = comparison else {
+        return;
+    };
+    let Ok([mut leader, mut follower]) = games.get_many_mut(comparison.games) else {
+        return;
+    };
+
+    if follower.2.is_some() {
+        mem::swap(&mut leader, &mut follower);
+    }
+
+    let (_, leader, _) = leader;
+    let (state, mut follower, _) = follower;
+
+    follower.paused = leader.paused;
+    follower.unlimited = leader.unlimited;
+    follower.turns_per_second = leader.turns_per_second;
+    if follower.turn != leader.turn {
+        follower.seek(leader.turn, state.board_count());
+    }
+}
+
+/// What each game answered on the shown turn, the diverging turns and both score curves
+pub fn draw_comparison(
+    comparison: Option<Res<Comparison>>,
+    panels: Res<Panels>,
+    args: Res<Args>,
+    mut egui_context: ResMut<EguiContext>,
+
+    games: Query<&State>,
+    mut focused: Query<(&State, &mut Playback), With<Focused>>,
+) {
+    let Some(comparison) = comparison else {
+        return;
+    };
+    let (Ok([before, after]), Ok((state, mut playback))) =
+        (games.get_many(comparison.games), focused.get_single_mut())
+    else {
+        return;
+    };
+    if !panels.shown {
+        return;
+    }
+
+    let turn = playback.turn;
+    let count = state.board_count();
+
+    egui::Window::new("Compare").show(egui_context.ctx_mut(), |ui| {
+        if !comparison.same_start {
+            ui.colored_label(
+                Color32::YELLOW,
+                "The games didn't start on the same map and seed",
+            );
+        }
+
+        let answer = |state: &State| match state.turns.get(turn).map(|t| t.answer.answer) {
+            Some(Some((x, y))) => format!("{x} {y}"),
+            Some(None) => "invalid".to_string(),
+            None => "-".to_string(),
+        };
+
+        if comparison.diverged.contains(&turn) {
+            ui.colored_label(Color32::RED, "Decisions diverged on this turn");
+        }
+        ui.label(format!("Before: {}", answer(before)));
+        ui.label(format!("After: {}", answer(after)));
+
+        ui.label(match comparison.boards_differ {
+            Some(board) => format!("Boards differ from board {board} on"),
+            None => "Both games have the same boards".to_string(),
+        });
+
+        ui.separator();
+        ui.label(format!("{} diverging decisions", comparison.diverged.len()));
+        egui::ScrollArea::vertical()
+            .max_height(120.0)
+            .show(ui, |ui| {
+                for i in &comparison.diverged {
+                    if ui
+                        .selectable_label(turn == *i, format!("Turn {i}"))
+                        .clicked()
+                    {
+                        playback.paused = true;
+                        playback.seek(*i, count);
+                    }
+                }
+            });
+
+        ui.separator();
+        Plot::new("compare_scores")
+            .height(200.0)
+            .legend(Legend::default())
+            .show(ui, |plot| {
+                let colors = [args.player1_color, args.player2_color].map(color32);
+
+                for (scores, (name, style)) in comparison.scores.iter().zip([
+                    ("before", LineStyle::Solid),
+                    ("after", LineStyle::dashed_loose()),
+                ]) {
+                    for (player, color) in colors.into_iter().enumerate() {
+                        let points: PlotPoints = scores
+                            .iter()
+                            .enumerate()
+                            .map(|(i, score)| {
+                                let tiles = if player == 0 { score.0 } else { score.1 };
+                                [i as f64, tiles as f64]
+                            })
+                            .collect();
+
+                        plot.line(
+                            Line::new(points)
+                                .color(color)
+                                .style(style)
+                                .name(format!("{name}, player {}", player + 1)),
+                        );
+                    }
+                }
+
+                let diverged: PlotPoints = comparison
+                    .diverged
+                    .iter()
+                    .map(|i| [*i as f64, 0.0])
+                    .collect();
+                plot.points(
+                    Points::new(diverged)
+                        .radius(3.0)
+                        .color(Color32::RED)
+                        .name("diverged"),
+                );
+
+                plot.vline(VLine::new(turn as f64).color(Color32::GRAY));
+            });
+    });
+}
+
+fn color32(rgb: Rgb) -> Color32 {
+    Color32::from_rgb(rgb.0, rgb.1, rgb.2)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse::{answer::Answer, piece::Piece, piece::PieceTile, turn::Turn};
+
+    use super::*;
+
+    /// One turn on the same board, player 1 places a standing domino at the answer
+    fn game(answer: (usize, usize), seed: &str) -> State {
+        let mut state = State {
+            turns: vec![Turn {
+                map: "...\n.@.\n..$".parse().unwrap(),
+                piece: Piece {
+                    start: 0,
+                    width: 1,
+                    heigth: 2,
+                    tiles: vec![vec![PieceTile::Piece], vec![PieceTile::Piece]],
+                },
+                answer: Answer {
+                    player: "@".to_string(),
+                    answer: Some(answer),
+                },
+            }],
+            ..Default::default()
+        };
+        state.last_map = state.final_map();
+        state.win.seed = seed.to_string();
+
+        state
+    }
+
+    fn compare(before: &State, after: &State) -> Comparison {
+        Comparison::new([Entity::from_raw(0), Entity::from_raw(1)], before, after)
+    }
+
+    #[test]
+    fn one_diverging_answer() {
+        let comparison = compare(&game((1, 0), "7"), &game((1, 0), "7"));
+        assert!(comparison.diverged.is_empty());
+        assert_eq!(comparison.boards_differ, None);
+        assert!(comparison.same_start);
+
+        // above player 1 or below it, the board before is the same and the one after isn't
+        let comparison = compare(&game((1, 0), "7"), &game((1, 1), "7"));
+        assert_eq!(comparison.diverged, vec![0]);
+        assert_eq!(comparison.boards_differ, Some(1));
+        assert!(comparison.same_start);
+        assert_eq!(comparison.scores[0], vec![(1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn same_start_needs_a_seed() {
+        assert!(!compare(&game((1, 0), ""), &game((1, 0), "")).same_start);
+        assert!(!compare(&game((1, 0), "7"), &game((1, 0), "8")).same_start);
+    }
+}
@@ -1,73 +1,271 @@
 use bevy::{
-    ecs::schedule::ShouldRun,
     prelude::{
-        Assets, Color, Commands, Component, Entity, Handle, Query, Res, ResMut, StandardMaterial,
+        AssetServer, Assets, Color, Commands, Component, DespawnRecursiveExt, Entity, Handle, Mesh,
+        Query, Res, ResMut, Resource, TextBundle, With,
     },
+    text::{TextAlignment, TextStyle},
+    time::Time,
+    ui::{PositionType, Style, UiRect, Val},
 };
 use bevy_egui::{egui, EguiContext};
 
-use crate::parse::{map::MapTile, State};
+use crate::{
+    analysis::moves::{Heuristics, MoveAnalysis},
+    board::Board,
+    games::Focused,
+    parse::State,
+    playback::Playback,
+    Args,
+};
+
+/// Marks the text shown over the board once the game is over, with the game it is about
+#[derive(Component)]
+pub struct WinText(pub Entity);
 
-#[derive(Component, Clone, Copy)]
-pub struct MapBlock {
-    pub x: usize,
-    pub y: usize,
+/// Whether the egui windows are shown, hiding them leaves only the board
+#[derive(Resource, Debug)]
+pub struct Panels {
+    pub shown: bool,
 }
 
-pub fn finished_parsing(state: Res<State>) -> ShouldRun {
-    if state.finished_parsing {
-        ShouldRun::Yes
-    } else {
-        ShouldRun::No
+impl Default for Panels {
+    fn default() -> Self {
+        Panels { shown: true }
     }
 }
 
-pub fn draw_info(state: Res<State>, mut egui_context: ResMut<EguiContext>) {
+pub fn draw_info(
+    panels: Res<Panels>,
+    mut egui_context: ResMut<EguiContext>,
+    games: Query<(&State, &Playback), With<Focused>>,
+) {
+    if !panels.shown {
+        return;
+    }
+
+    let Ok((state, playback)) = games.get_single() else {
+        return;
+    };
+
     egui::Window::new("Players").show(egui_context.ctx_mut(), |ui| {
         ui.label(format!("{}", state.player1));
         ui.label(format!("{}", state.player2));
     });
 
+    // the final board has no turn of its own, nothing was answered on it
+    let Some(turn) = state.turns.get(playback.turn) else {
+        return;
+    };
+
     egui::Window::new("Answer").show(egui_context.ctx_mut(), |ui| {
-        ui.label(format!("{}", state.answer));
+        ui.label(format!("{}", turn.answer));
     });
 
     egui::Window::new("Piece").show(egui_context.ctx_mut(), |ui| {
-        ui.label(format!("{}", state.piece));
+        ui.label(format!("{}", turn.piece));
+    });
+}
+
+/// Every answer so far with the piece it placed, clicking one shows that turn
+pub fn draw_move_log(
+    panels: Res<Panels>,
+    mut egui_context: ResMut<EguiContext>,
+    heuristics: Res<Heuristics>,
+    mut games: Query<(&State, &mut Playback, Option<&MoveAnalysis>), With<Focused>>,
+) {
+    if !panels.shown {
+        return;
+    }
+
+    let Ok((state, mut playback, analysis)) = games.get_single_mut() else {
+        return;
+    };
+
+    let count = state.board_count();
+
+    egui::Window::new("Moves").show(egui_context.ctx_mut(), |ui| {
+        egui::ScrollArea::vertical()
+            .max_height(300.0)
+            .show(ui, |ui| {
+                for (i, turn) in state.turns.iter().enumerate() {
+                    let answer = match turn.answer.answer {
+                        Some((x, y)) => format!("{x} {y}"),
+                        None => "invalid".to_string(),
+                    };
+                    let mut text = egui::RichText::new(format!(
+                        "{i}: ({}) {answer}, piece {}x{}",
+                        turn.answer.player, turn.piece.width, turn.piece.heigth
+                    ));
+
+                    // blunders stand out so they are found without opening the analysis
+                    let rank = analysis
+                        .and_then(|a| a.ranks.get(i))
+                        .and_then(Option::as_ref);
+                    if rank.is_some_and(|rank| rank.is_blunder(&heuristics)) {
+                        text = text.color(egui::Color32::RED);
+                    }
+
+                    let entry = ui
+                        .selectable_label(playback.turn == i, text)
+                        .on_hover_ui(|ui| {
+                            ui.monospace(format!("{}", turn.piece));
+                        });
+                    if entry.clicked() {
+                        playback.paused = true;
+                        playback.seek(i, count);
+                    }
+                }
+            });
     });
 }
 
-pub fn draw_cubes(
+/// Banner over the board once the engine output stopped before the game finished,
+/// everything parsed until then can still be played back
+pub fn draw_stream_end(mut egui_context: ResMut<EguiContext>, games: Query<&State, With<Focused>>) {
+    let Some((state, end)) = games
+        .get_single()
+        .ok()
+        .and_then(|state| Some((state, state.stream_end.as_ref()?)))
+    else {
+        return;
+    };
+
+    egui::Area::new("stream_end")
+        .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 8.0))
+        .show(egui_context.ctx_mut(), |ui| {
+            egui::Frame::popup(ui.style())
+                .fill(egui::Color32::from_rgb(120, 30, 30))
+                .show(ui, |ui| {
+                    ui.colored_label(egui::Color32::WHITE, format!("Stream ended: {end}"));
+                    ui.colored_label(
+                        egui::Color32::LIGHT_GRAY,
+                        format!("{} turns were received", state.turns.len()),
+                    );
+                });
+        });
+}
+
+type BoardQuery<'a> = (
+    Entity,
+    &'a State,
+    &'a Playback,
+    Option<&'a mut Board>,
+    &'a mut Handle<Mesh>,
+);
+
+/// Draws the shown board of every game, the mesh is only rebuilt when the size changes
+pub fn draw_board(
     mut commands: Commands,
-    state: Res<State>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    args: Res<Args>,
+    time: Res<Time>,
+    mut meshes: ResMut<Assets<Mesh>>,
 
-    mut cubes: Query<(Entity, &mut Handle<StandardMaterial>, &MapBlock)>,
+    mut games: Query<BoardQuery>,
 ) {
-    let red = materials.add(Color::RED.into());
-    let blue = materials.add(Color::BLUE.into());
-    let white = materials.add(Color::WHITE.into());
-
-    for (entity, mut material, cube) in cubes.iter_mut() {
-        if !state.map.tiles.is_empty() {
-            match state.map.tiles.get(cube.y) {
-                Some(r) => match r.get(cube.x) {
-                    Some(t) => match t {
-                        MapTile::Player1 => *material = red.clone(),
-                        MapTile::Player2 => *material = blue.clone(),
-                        MapTile::Empty => *material = white.clone(),
-                        MapTile::None => commands.entity(entity).despawn(),
-                    },
-                    None => commands.entity(entity).despawn(),
-                },
-                None => commands.entity(entity).despawn(),
+    for (entity, state, playback, board, mut handle) in games.iter_mut() {
+        let Some(map) = state.board(playback.turn) else {
+            continue;
+        };
+
+        if let Some(mut board) = board.filter(|board| board.fits(map)) {
+            if board.turn == playback.turn {
+                continue;
+            }
+
+            // only a single step is animated, jumps and fast playback just show the result
+            let animate = board.turn + 1 == playback.turn && !playback.unlimited;
+            let now = animate.then(|| time.elapsed_seconds_f64());
+
+            if let Some(mesh) = meshes.get_mut(&handle) {
+                board.update(mesh, map, &state.claims, &args, now);
+                board.turn = playback.turn;
             }
-        } else {
-            println!("This shouldn't happen")
+            continue;
         }
+
+        let (mut board, mesh) = Board::new(map, &state.claims, &args);
+        board.turn = playback.turn;
+
+        *handle = meshes.add(mesh);
+        commands.entity(entity).insert(board);
     }
 }
 
-pub fn finish_drawing(mut state: ResMut<State>) {
-    state.finished_parsing = false;
+/// Shows who won while the final board is on screen
+pub fn draw_win(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+
+    games: Query<(Entity, &State, &Playback), With<Focused>>,
+    texts: Query<(Entity, &WinText)>,
+) {
+    let game = games.get_single().ok().filter(|(_, state, playback)| {
+        state.win.finished && playback.turn + 1 >= state.board_count()
+    });
+    let wanted = game.map(|(entity, _, _)| entity);
+    let shown = texts.iter().next().map(|(_, text)| text.0);
+
+    if wanted == shown {
+        return;
+    }
+
+    for (entity, _) in texts.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let Some((entity, state, _)) = game else {
+        return;
+    };
+
+    let font = asset_server.load("fonts/Roboto-Regular.ttf");
+
+    commands.spawn((
+        TextBundle::from_section(
+            match state.win.outcome {
+                Some(outcome) => outcome.to_string(),
+                None => "Game over".to_string(),
+            },
+            TextStyle {
+                font: font.clone(),
+                font_size: 60.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_text_alignment(TextAlignment::CENTER_LEFT)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                bottom: Val::Percent(50.0),
+                right: Val::Percent(40.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        }),
+        WinText(entity),
+    ));
+
+    commands.spawn((
+        TextBundle::from_section(
+            format!(
+                "Player 1 score: {}\nPlayer 2 score: {}",
+                state.win.player1_score, state.win.player2_score
+            ),
+            TextStyle {
+                font,
+                font_size: 30.0,
+                color: Color::GRAY,
+            },
+        )
+        .with_text_alignment(TextAlignment::CENTER_LEFT)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                bottom: Val::Percent(40.0),
+                right: Val::Percent(43.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        }),
+        WinText(entity),
+    ));
 }
@@ -0,0 +1,21 @@
+use std::io;
+
+use crate::parse::replay::Replay;
+
+use super::raster::Style;
+
+const TEMPLATE: &str = include_str!("../../assets/replay.html");
+
+/// Fills the replay page template with the game and colors
+pub fn render(replay: &Replay, style: &Style) -> io::Result<String> {
+    let json = serde_json::to_string(replay).map_err(io::Error::other)?;
+    let colors = format!(
+        r#"{{ "player1": "{}", "player2": "{}" }}"#,
+        style.player1, style.player2
+    );
+
+    // "</script>" inside a player path would otherwise end the script early
+    Ok(TEMPLATE
+        .replace("/*REPLAY*/", &json.replace("</", "<\\/"))
+        .replace("/*COLORS*/", &colors))
+}
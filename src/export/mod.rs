@@ -0,0 +1,182 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use clap::Args as ClapArgs;
+
+use crate::{
+    parse::{
+        map::{Map, MapTile},
+        replay::Replay,
+        State,
+    },
+    Args,
+};
+
+pub mod html;
+pub mod raster;
+pub mod svg;
+
+#[derive(ClapArgs, Clone, Debug)]
+pub struct ExportArgs {
+    /// Engine output or json replay of the game, reads stdin if not given
+    input: Option<PathBuf>,
+
+    /// Writes the game as an animated gif
+    #[arg(long)]
+    gif: Option<PathBuf>,
+
+    /// Writes every turn as a png into this directory
+    #[arg(long)]
+    png: Option<PathBuf>,
+
+    /// Writes a replay page that plays in the browser
+    #[arg(long)]
+    html: Option<PathBuf>,
+
+    /// Writes the parsed game as a json replay
+    #[arg(long)]
+    json: Option<PathBuf>,
+
+    /// Writes a single turn as an svg image
+    #[arg(long)]
+    svg: Option<PathBuf>,
+
+    /// Turn the svg shows, the final board if not given
+    #[arg(long, requires = "svg")]
+    turn: Option<usize>,
+
+    /// Size of one tile in pixels
+    #[arg(long, default_value_t = 8)]
+    cell_size: u32,
+
+    /// How long each turn is shown in the gif, in milliseconds
+    #[arg(long, default_value_t = 100)]
+    delay: u32,
+}
+
+/// One exported image, the board and what the header says about it
+pub struct Frame<'a> {
+    pub turn: usize,
+    pub map: &'a Map,
+    pub previous: Option<&'a Map>,
+}
+
+pub fn export(args: &Args, export_args: &ExportArgs) -> io::Result<()> {
+    let outputs = [
+        &export_args.gif,
+        &export_args.png,
+        &export_args.html,
+        &export_args.json,
+        &export_args.svg,
+    ];
+    if outputs.iter().all(|o| o.is_none()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "nothing to export, pass --gif, --png, --html, --json or --svg",
+        ));
+    }
+
+    let replay = load(export_args.input.as_deref(), args.debug)?;
+    let boards = replay.boards();
+    if boards.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the input doesn't contain a single turn",
+        ));
+    }
+
+    eprintln!("{}", summary(&replay));
+
+    let style = raster::Style {
+        cell_size: export_args.cell_size.max(1),
+        player1: args.player1_color,
+        player2: args.player2_color,
+    };
+
+    let images = boards.iter().enumerate().map(|(turn, map)| {
+        let frame = Frame {
+            turn,
+            map,
+            previous: turn.checked_sub(1).map(|i| boards[i]),
+        };
+
+        raster::render(&replay, &frame, boards.len() - 1, &style)
+    });
+
+    if let Some(path) = &export_args.json {
+        let json = serde_json::to_string(&replay).map_err(io::Error::other)?;
+        fs::write(path, json)?;
+    }
+
+    if let Some(path) = &export_args.svg {
+        let turn = export_args.turn.unwrap_or(boards.len() - 1);
+        let svg = svg::render(&replay, turn, &style)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        fs::write(path, svg)?;
+    }
+
+    if let Some(path) = &export_args.html {
+        fs::write(path, html::render(&replay, &style)?)?;
+    }
+
+    if let Some(dir) = &export_args.png {
+        fs::create_dir_all(dir)?;
+
+        for (turn, image) in images.clone().enumerate() {
+            image
+                .save(dir.join(format!("turn_{turn:04}.png")))
+                .map_err(io::Error::other)?;
+        }
+    }
+
+    if let Some(path) = &export_args.gif {
+        raster::write_gif(path, images, export_args.delay)?;
+    }
+
+    Ok(())
+}
+
+/// Reads the game from a file or stdin, either a json replay or the engine output
+pub fn load(input: Option<&Path>, debug: bool) -> io::Result<Replay> {
+    let text = match input {
+        Some(path) => fs::read_to_string(path)?,
+        None => io::read_to_string(io::stdin())?,
+    };
+
+    if text.trim_start().starts_with('{') {
+        return serde_json::from_str(&text)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e));
+    }
+
+    let state = State::from_reader(text.as_bytes(), debug)?;
+    Ok(Replay::from(&state))
+}
+
+/// Result of the game for the terminal, how many turns it took and how it ended
+pub fn summary(replay: &Replay) -> String {
+    let outcome = match replay.win.outcome {
+        Some(outcome) => outcome.to_string(),
+        None => "No result".to_string(),
+    };
+
+    format!(
+        "{} turns, {}: {}, {}: {}, {outcome}",
+        replay.turns.len(),
+        replay.player1.path,
+        replay.win.player1_score,
+        replay.player2.path,
+        replay.win.player2_score,
+    )
+}
+
+/// Tiles that changed owner since the previous board
+pub fn changed(map: &Map, previous: Option<&Map>, x: usize, y: usize) -> bool {
+    let Some(previous) = previous else {
+        return false;
+    };
+
+    let before = previous.tiles.get(y).and_then(|r| r.get(x));
+    before.is_some_and(|t| *t == MapTile::Empty) && map.tiles[y][x] != MapTile::Empty
+}
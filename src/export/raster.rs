@@ -0,0 +1,143 @@
+use std::{fs::File, io, path::Path};
+
+use ab_glyph::{point, Font, FontRef, PxScale, ScaleFont};
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    Delay, Rgba, RgbaImage,
+};
+
+use crate::{color::Rgb, parse::map::MapTile, parse::replay::Replay};
+
+use super::{changed, Frame};
+
+const FONT: &[u8] = include_bytes!("../../assets/fonts/Roboto-Regular.ttf");
+const HEADER_HEIGHT: u32 = 24;
+const HEADER_FONT_SIZE: f32 = 16.0;
+const BACKGROUND: Rgba<u8> = Rgba([0, 0, 0, 255]);
+const TEXT: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+pub struct Style {
+    pub cell_size: u32,
+    pub player1: Rgb,
+    pub player2: Rgb,
+}
+
+impl Style {
+    pub fn tile_color(&self, tile: MapTile, last_move: bool) -> Rgb {
+        let color = match tile {
+            MapTile::Player1 => self.player1,
+            MapTile::Player2 => self.player2,
+            _ => return Rgb::WHITE,
+        };
+
+        if last_move {
+            color.lighten(0.5)
+        } else {
+            color
+        }
+    }
+}
+
+/// Draws the board with a score header on the cpu
+pub fn render(replay: &Replay, frame: &Frame, last_turn: usize, style: &Style) -> RgbaImage {
+    let map = frame.map;
+    let cell = style.cell_size;
+
+    let mut header = format!(
+        "Turn {}/{}    {}: {}    {}: {}",
+        frame.turn,
+        last_turn,
+        replay.player1.path,
+        map.count(MapTile::Player1),
+        replay.player2.path,
+        map.count(MapTile::Player2),
+    );
+    if let (true, Some(outcome)) = (frame.turn == last_turn, replay.win.outcome) {
+        header += &format!("    {outcome}");
+    }
+
+    let header_width = text_width(&header, HEADER_FONT_SIZE).ceil() as u32 + 8;
+    let width = (map.width as u32 * cell).max(header_width);
+    let height = HEADER_HEIGHT + map.heigth as u32 * cell;
+
+    let mut image = RgbaImage::from_pixel(width, height, BACKGROUND);
+    draw_text(&mut image, &header, 4.0, 4.0, HEADER_FONT_SIZE);
+
+    // leaves a one pixel gap between tiles when they are big enough to show it
+    let gap = u32::from(cell >= 4);
+
+    for (y, row) in map.tiles.iter().enumerate() {
+        for (x, tile) in row.iter().enumerate() {
+            if *tile == MapTile::None {
+                continue;
+            }
+
+            let Rgb(r, g, b) = style.tile_color(*tile, changed(map, frame.previous, x, y));
+            let (left, top) = (x as u32 * cell, HEADER_HEIGHT + y as u32 * cell);
+
+            for py in top..top + cell - gap {
+                for px in left..left + cell - gap {
+                    image.put_pixel(px, py, Rgba([r, g, b, 255]));
+                }
+            }
+        }
+    }
+
+    image
+}
+
+pub fn text_width(text: &str, size: f32) -> f32 {
+    let font = FontRef::try_from_slice(FONT).unwrap();
+    let font = font.as_scaled(PxScale::from(size));
+
+    text.chars().map(|c| font.h_advance(font.glyph_id(c))).sum()
+}
+
+fn draw_text(image: &mut RgbaImage, text: &str, x: f32, y: f32, size: f32) {
+    let font = FontRef::try_from_slice(FONT).unwrap();
+    let font = font.as_scaled(PxScale::from(size));
+
+    let mut caret = x;
+    for c in text.chars() {
+        let glyph = font
+            .glyph_id(c)
+            .with_scale_and_position(size, point(caret, y + font.ascent()));
+        caret += font.h_advance(glyph.id);
+
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            let px = bounds.min.x as i32 + gx as i32;
+            let py = bounds.min.y as i32 + gy as i32;
+
+            if px < 0 || py < 0 || px >= image.width() as i32 || py >= image.height() as i32 {
+                return;
+            }
+
+            let pixel = image.get_pixel_mut(px as u32, py as u32);
+            for i in 0..3 {
+                pixel[i] = (pixel[i] as f32 * (1.0 - coverage) + TEXT[i] as f32 * coverage) as u8;
+            }
+        });
+    }
+}
+
+pub fn write_gif(
+    path: &Path,
+    images: impl Iterator<Item = RgbaImage>,
+    delay_ms: u32,
+) -> io::Result<()> {
+    let mut encoder = GifEncoder::new_with_speed(File::create(path)?, 10);
+    encoder
+        .set_repeat(Repeat::Infinite)
+        .map_err(io::Error::other)?;
+
+    let frames = images.map(|image| {
+        image::Frame::from_parts(image, 0, 0, Delay::from_numer_denom_ms(delay_ms, 1))
+    });
+
+    encoder.encode_frames(frames).map_err(io::Error::other)
+}
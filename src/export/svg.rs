@@ -0,0 +1,207 @@
+use std::fmt::Write;
+
+use crate::{
+    color::Rgb,
+    parse::{map::MapTile, piece::PieceTile, replay::Replay},
+};
+
+use super::{
+    changed,
+    raster::{text_width, Style},
+};
+
+const CELL: usize = 20;
+const MARGIN_LEFT: usize = 40;
+const MARGIN_TOP: usize = 48;
+
+/// Draws one turn as a vector image, with the piece placed at the answer and a coordinate grid
+pub fn render(replay: &Replay, turn: usize, style: &Style) -> Result<String, String> {
+    let boards = replay.boards();
+    let map = *boards.get(turn).ok_or(format!(
+        "turn {turn} doesn't exist, the last turn is {}",
+        boards.len() - 1
+    ))?;
+    let previous = turn.checked_sub(1).map(|i| boards[i]);
+
+    let header = format!(
+        "Turn {turn}/{}    {}: {}    {}: {}",
+        boards.len() - 1,
+        replay.player1.path,
+        map.count(MapTile::Player1),
+        replay.player2.path,
+        map.count(MapTile::Player2),
+    );
+    let answer_text = replay.turns.get(turn).map(|t| t.answer.to_string());
+
+    // the header is measured with the font the viewer most likely has, so small maps
+    // don't cut off the names and scores
+    let header_width = text_width(&header, 14.0)
+        .max(answer_text.as_deref().map_or(0.0, |a| text_width(a, 12.0)))
+        .ceil() as usize
+        + 8;
+    let width = (MARGIN_LEFT + map.width * CELL + 8).max(header_width);
+    let height = MARGIN_TOP + map.heigth * CELL + 8;
+    let mut svg = String::new();
+
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="Roboto, sans-serif">"#
+    )
+    .unwrap();
+    writeln!(svg, r##"<rect width="100%" height="100%" fill="#000"/>"##).unwrap();
+    writeln!(
+        svg,
+        r##"<text x="4" y="18" font-size="14" fill="#fff">{}</text>"##,
+        escape(&header),
+    )
+    .unwrap();
+
+    // labels every column when there is room for them, otherwise every fifth
+    let step = if map.width.max(map.heigth) > 40 { 5 } else { 1 };
+    for x in (0..map.width).step_by(step) {
+        writeln!(
+            svg,
+            r##"<text x="{}" y="{}" font-size="9" fill="#aaa" text-anchor="middle">{x}</text>"##,
+            MARGIN_LEFT + x * CELL + CELL / 2,
+            MARGIN_TOP - 6,
+        )
+        .unwrap();
+    }
+    for y in (0..map.heigth).step_by(step) {
+        writeln!(
+            svg,
+            r##"<text x="{}" y="{}" font-size="9" fill="#aaa" text-anchor="end">{y}</text>"##,
+            MARGIN_LEFT - 6,
+            MARGIN_TOP + y * CELL + CELL / 2 + 3,
+        )
+        .unwrap();
+    }
+
+    for (y, row) in map.tiles.iter().enumerate() {
+        for (x, tile) in row.iter().enumerate() {
+            if *tile == MapTile::None {
+                continue;
+            }
+
+            writeln!(
+                svg,
+                r##"<rect x="{}" y="{}" width="{CELL}" height="{CELL}" fill="{}" stroke="#444" stroke-width="1"/>"##,
+                MARGIN_LEFT + x * CELL,
+                MARGIN_TOP + y * CELL,
+                style.tile_color(*tile, changed(map, previous, x, y)),
+            )
+            .unwrap();
+        }
+    }
+
+    if let Some(t) = replay.turns.get(turn) {
+        // an answer off the board has nothing to outline
+        let answer = t
+            .answer
+            .answer
+            .filter(|(x, y)| *x < map.width && *y < map.heigth);
+        if let Some((ax, ay)) = answer {
+            let color = match t.answer.tile() {
+                Some(MapTile::Player1) => style.player1,
+                Some(MapTile::Player2) => style.player2,
+                _ => Rgb(128, 128, 128),
+            };
+
+            writeln!(
+                svg,
+                r##"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="#ffd700" stroke-width="2" stroke-dasharray="4 3"/>"##,
+                MARGIN_LEFT + ax * CELL,
+                MARGIN_TOP + ay * CELL,
+                t.piece.tiles.first().map_or(0, Vec::len).min(map.width - ax) * CELL,
+                t.piece.tiles.len().min(map.heigth - ay) * CELL,
+            )
+            .unwrap();
+
+            for (py, row) in t.piece.tiles.iter().enumerate() {
+                for (px, tile) in row.iter().enumerate() {
+                    // an answer the engine rejected can hang over the edge of the board
+                    if *tile != PieceTile::Piece || ax + px >= map.width || ay + py >= map.heigth {
+                        continue;
+                    }
+
+                    writeln!(
+                        svg,
+                        r##"<rect x="{}" y="{}" width="{}" height="{}" fill="{color}" fill-opacity="0.6" stroke="#ffd700" stroke-width="2"/>"##,
+                        MARGIN_LEFT + (ax + px) * CELL + 3,
+                        MARGIN_TOP + (ay + py) * CELL + 3,
+                        CELL - 6,
+                        CELL - 6,
+                    )
+                    .unwrap();
+                }
+            }
+        }
+
+        writeln!(
+            svg,
+            r##"<text x="4" y="34" font-size="12" fill="#ffd700">{}</text>"##,
+            escape(answer_text.as_deref().unwrap_or_default()),
+        )
+        .unwrap();
+    }
+
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::{color::Rgb, parse::State};
+
+    use super::*;
+
+    fn tiny_map() -> Replay {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/tiny_map.txt");
+        let text = fs::read_to_string(path).unwrap();
+        Replay::from(&State::from_reader(text.as_bytes(), false).unwrap())
+    }
+
+    fn style() -> Style {
+        Style {
+            cell_size: 10,
+            player1: Rgb(255, 0, 0),
+            player2: Rgb(0, 0, 255),
+        }
+    }
+
+    /// Numeric attribute of an element, None if it has no such attribute
+    fn attribute(element: &str, name: &str) -> Option<usize> {
+        let rest = element.split(&format!(" {name}=\"")).nth(1)?;
+        rest.split('"').next()?.parse().ok()
+    }
+
+    #[test]
+    fn header_fits_on_small_maps() {
+        let replay = tiny_map();
+        let svg = render(&replay, 0, &style()).unwrap();
+
+        let width = attribute(svg.lines().next().unwrap(), "width").unwrap();
+        assert!(width > MARGIN_LEFT + 2 * CELL + 8);
+        assert!(width as f32 >= text_width("robots/bender", 14.0) * 2.0);
+    }
+
+    #[test]
+    fn piece_is_clipped_to_the_board() {
+        // the answer at (0, 0) has the three rows high piece hang below the two rows of the board
+        let svg = render(&tiny_map(), 0, &style()).unwrap();
+
+        let bottom = MARGIN_TOP + 2 * CELL;
+        for rect in svg.lines().filter(|line| line.starts_with("<rect x=")) {
+            let (y, height) = (attribute(rect, "y"), attribute(rect, "height"));
+            assert!(y.unwrap() + height.unwrap() <= bottom, "{rect}");
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use bevy::{
+    prelude::{
+        Assets, Color, Commands, Component, Entity, PbrBundle, Query, Res, ResMut, Resource,
+        StandardMaterial, Transform, Vec3, Visibility, With,
+    },
+    render::view::NoFrustumCulling,
+};
+use bevy_egui::{egui, EguiContext};
+
+use crate::{
+    board::Board,
+    draw::Panels,
+    parse::{listen::Listener, Lines, State},
+    playback::Playback,
+    Args,
+};
+
+/// Empty tiles left between the boards in the grid
+const GRID_GAP: f32 = 4.0;
+
+/// One of the games being shown, every game is an entity with its own state, playback and board
+#[derive(Component, Debug)]
+pub struct Game {
+    /// File or connection the game came from
+    pub name: String,
+    /// Order the games were opened in, the tabs and the grid keep it
+    pub index: usize,
+}
+
+/// Marks the game the panels, keys and camera work on
+#[derive(Component)]
+pub struct Focused;
+
+/// How the games are laid out when there is more than one
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameView {
+    /// Only the focused game is shown
+    #[default]
+    Tabs,
+    /// Every game is shown next to each other
+    Grid,
+}
+
+impl GameView {
+    pub fn switch(&mut self) {
+        *self = match self {
+            GameView::Tabs => GameView::Grid,
+            GameView::Grid => GameView::Tabs,
+        }
+    }
+}
+
+/// Spawns a game without a board, it is drawn once the state has one
+pub fn spawn_game(
+    commands: &mut Commands,
+    materials: &mut Assets<StandardMaterial>,
+    game: Game,
+    state: State,
+    args: &Args,
+    lines: Option<Lines>,
+) -> Entity {
+    let mut entity = commands.spawn((
+        PbrBundle {
+            // the tile colors come from the mesh, the material only adds the lighting
+            material: materials.add(Color::WHITE.into()),
+            ..Default::default()
+        },
+        // the mesh is swapped when the board changes size, its old bounds would cull the new one
+        NoFrustumCulling,
+        game,
+        state,
+        Playback::new(args),
+    ));
+
+    if let Some(lines) = lines {
+        entity.insert(lines);
+    }
+
+    entity.id()
+}
+
+/// Moves the focus to another game
+pub fn focus(commands: &mut Commands, focused: impl IntoIterator<Item = Entity>, game: Entity) {
+    for entity in focused {
+        commands.entity(entity).remove::<Focused>();
+    }
+
+    commands.entity(game).insert(Focused);
+}
+
+/// Turns every connection the listener accepted into a game of its own
+pub fn accept_games(
+    mut commands: Commands,
+    listener: Option<Res<Listener>>,
+    args: Res<Args>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+
+    games: Query<&Game>,
+    focused: Query<Entity, With<Focused>>,
+) {
+    let Some(listener) = listener else {
+        return;
+    };
+
+    let mut unfocused = focused.is_empty();
+
+    for (index, (name, lines)) in (games.iter().count()..).zip(listener.accept()) {
+        let game = Game { name, index };
+        let entity = spawn_game(
+            &mut commands,
+            &mut materials,
+            game,
+            State::default(),
+            &args,
+            Some(lines),
+        );
+
+        // the first connection gets the focus, later ones wait in their tab
+        if unfocused {
+            commands.entity(entity).insert(Focused);
+            unfocused = false;
+        }
+    }
+}
+
+type LayoutQuery<'a> = (
+    &'a Game,
+    &'a mut Transform,
+    &'a mut Visibility,
+    Option<&'a Board>,
+    Option<&'a Focused>,
+);
+
+/// Places the boards, the focused one alone at the origin as tabs or all of them as a grid
+pub fn layout_games(view: Res<GameView>, mut games: Query<LayoutQuery>) {
+    // every cell fits the biggest board, so boards of different sizes still line up
+    let cell = games
+        .iter()
+        .filter_map(|(_, _, _, board, _)| board)
+        .map(|board| board.width.max(board.heigth) as f32)
+        .fold(0.0, f32::max)
+        + GRID_GAP;
+    let columns = (games.iter().count() as f32).sqrt().ceil().max(1.0) as usize;
+
+    for (game, mut transform, mut visibility, _, focused) in games.iter_mut() {
+        let (translation, shown) = match *view {
+            GameView::Tabs => (Vec3::ZERO, focused.is_some()),
+            GameView::Grid => {
+                let column = (game.index % columns) as f32;
+                let row = (game.index / columns) as f32;
+                (Vec3::new(column * cell, 0.0, row * cell), true)
+            }
+        };
+
+        if transform.translation != translation {
+            transform.translation = translation;
+        }
+        if visibility.is_visible != shown {
+            visibility.is_visible = shown;
+        }
+    }
+}
+
+/// Tabs for every game and the switch between tabs and grid, hidden while there is only one game
+pub fn draw_games(
+    mut commands: Commands,
+    panels: Res<Panels>,
+    mut view: ResMut<GameView>,
+    mut egui_context: ResMut<EguiContext>,
+
+    games: Query<(Entity, &Game, &State, Option<&Focused>)>,
+    focused: Query<Entity, With<Focused>>,
+) {
+    if !panels.shown || games.iter().count() < 2 {
+        return;
+    }
+
+    let mut games: Vec<_> = games.iter().collect();
+    games.sort_by_key(|(_, game, _, _)| game.index);
+
+    egui::Window::new("Games").show(egui_context.ctx_mut(), |ui| {
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut *view, GameView::Tabs, "Tabs");
+            ui.selectable_value(&mut *view, GameView::Grid, "Grid");
+        });
+        ui.separator();
+
+        for (entity, game, state, is_focused) in games {
+            let status = match (state.win.outcome, state.win.finished) {
+                (Some(outcome), _) => outcome.to_string(),
+                (None, true) => "Game over".to_string(),
+                (None, false) => format!("turn {}", state.turns.len()),
+            };
+
+            if ui
+                .selectable_label(is_focused.is_some(), format!("{}: {status}", game.name))
+                .clicked()
+            {
+                focus(&mut commands, focused.iter(), entity);
+            }
+        }
+    });
+}
+
+/// Moves the focus to the game opened after the focused one, wrapping around
+pub fn next_game(commands: &mut Commands, games: impl IntoIterator<Item = (Entity, usize, bool)>) {
+    let mut games: Vec<_> = games.into_iter().collect();
+    games.sort_by_key(|(_, index, _)| *index);
+
+    let Some(current) = games.iter().position(|(_, _, focused)| *focused) else {
+        return;
+    };
+    let (focused, _, _) = games[current];
+    let (next, _, _) = games[(current + 1) % games.len()];
+
+    focus(commands, [focused], next);
+}
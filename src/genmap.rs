@@ -0,0 +1,214 @@
+use std::{fs, io, path::PathBuf};
+
+use clap::{Args as ClapArgs, ValueEnum};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::parse::map::{Map, MapTile};
+
+#[derive(ClapArgs, Clone, Debug)]
+pub struct GenMapArgs {
+    /// Width of the map
+    #[arg(long, default_value_t = 20)]
+    width: usize,
+
+    /// Height of the map
+    #[arg(long, default_value_t = 15)]
+    height: usize,
+
+    /// How player 2 mirrors player 1
+    #[arg(short, long, value_enum, default_value_t = Symmetry::Rotational)]
+    symmetry: Symmetry,
+
+    /// Fraction of the map covered by pre-filled regions, at least 0 and less than 1
+    #[arg(short, long, default_value_t = 0.0)]
+    density: f64,
+
+    /// Seed for the random generator, random if not given
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// File to write the map to, prints to stdout if not given
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symmetry {
+    /// Player 2 is mirrored along the vertical axis
+    Mirror,
+    /// Player 2 is rotated 180 degrees around the center
+    Rotational,
+}
+
+impl Symmetry {
+    fn apply(self, (x, y): (usize, usize), width: usize, heigth: usize) -> (usize, usize) {
+        match self {
+            Symmetry::Mirror => (width - 1 - x, y),
+            Symmetry::Rotational => (width - 1 - x, heigth - 1 - y),
+        }
+    }
+}
+
+pub fn genmap(args: &GenMapArgs) -> io::Result<()> {
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let map = generate(args, seed).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let text = map.to_map_file();
+
+    // the generated file has to be readable by the map parser as is
+    debug_assert_eq!(text.parse::<Map>().map(|m| m.tiles), Ok(map.tiles));
+
+    match &args.output {
+        Some(path) => {
+            fs::write(path, text)?;
+            eprintln!("Wrote {}x{} map with seed {seed}", args.width, args.height);
+        }
+        None => print!("{text}"),
+    }
+
+    Ok(())
+}
+
+pub fn generate(args: &GenMapArgs, seed: u64) -> Result<Map, String> {
+    let (width, heigth) = (args.width, args.height);
+    if width < 2 || heigth < 2 {
+        return Err("map has to be at least 2x2".to_string());
+    }
+    // a full map leaves no room to place a piece
+    if !(0.0..1.0).contains(&args.density) {
+        return Err("density has to be at least 0 and less than 1".to_string());
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut tiles = vec![vec![MapTile::Empty; width]; heigth];
+
+    // start position is picked from the half of the map that belongs to player 1,
+    // so it never lands on its own mirror image
+    let start = loop {
+        let pos = (
+            rng.gen_range(0..width.div_ceil(2)),
+            rng.gen_range(0..heigth),
+        );
+        if args.symmetry.apply(pos, width, heigth) != pos {
+            break pos;
+        }
+    };
+    place(&mut tiles, start, args.symmetry, MapTile::Player1);
+
+    // regions grow as random walks from a seed cell and are mirrored to player 2,
+    // only half of the target is grown since mirroring doubles it
+    let target = (args.density * (width * heigth) as f64 / 2.0) as usize;
+    let mut filled = 0;
+    let mut attempts = 0;
+    while filled < target && attempts < width * heigth * 10 {
+        attempts += 1;
+
+        let mut pos = (rng.gen_range(0..width), rng.gen_range(0..heigth));
+        let size = rng.gen_range(1..=(target - filled).min(8));
+
+        for _ in 0..size {
+            let mirrored = args.symmetry.apply(pos, width, heigth);
+            if mirrored != pos
+                && tiles[pos.1][pos.0] == MapTile::Empty
+                && tiles[mirrored.1][mirrored.0] == MapTile::Empty
+            {
+                place(&mut tiles, pos, args.symmetry, MapTile::Player1);
+                filled += 1;
+            }
+
+            pos = match rng.gen_range(0..4) {
+                0 => (pos.0.saturating_sub(1), pos.1),
+                1 => ((pos.0 + 1).min(width - 1), pos.1),
+                2 => (pos.0, pos.1.saturating_sub(1)),
+                _ => (pos.0, (pos.1 + 1).min(heigth - 1)),
+            };
+        }
+    }
+
+    Ok(Map {
+        start: 0,
+        width,
+        heigth,
+        tiles,
+    })
+}
+
+fn place(tiles: &mut [Vec<MapTile>], pos: (usize, usize), symmetry: Symmetry, tile: MapTile) {
+    let (width, heigth) = (tiles[0].len(), tiles.len());
+    let mirrored = symmetry.apply(pos, width, heigth);
+
+    tiles[pos.1][pos.0] = tile;
+    tiles[mirrored.1][mirrored.0] = match tile {
+        MapTile::Player1 => MapTile::Player2,
+        MapTile::Player2 => MapTile::Player1,
+        other => other,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(symmetry: Symmetry, density: f64) -> GenMapArgs {
+        GenMapArgs {
+            width: 20,
+            height: 15,
+            symmetry,
+            density,
+            seed: None,
+            output: None,
+        }
+    }
+
+    #[test]
+    fn maps_round_trip_and_mirror() {
+        for symmetry in [Symmetry::Mirror, Symmetry::Rotational] {
+            for density in [0.0, 0.3] {
+                for seed in [0, 1, 7, 42] {
+                    let args = args(symmetry, density);
+                    let map = generate(&args, seed).unwrap();
+
+                    let parsed: Map = map.to_map_file().parse().unwrap();
+                    assert_eq!(parsed.tiles, map.tiles, "{symmetry:?} seed {seed}");
+                    assert_eq!((parsed.width, parsed.heigth), (20, 15));
+
+                    for (y, row) in map.tiles.iter().enumerate() {
+                        for (x, tile) in row.iter().enumerate() {
+                            let (mx, my) = symmetry.apply((x, y), 20, 15);
+                            let expected = match tile {
+                                MapTile::Player1 => MapTile::Player2,
+                                MapTile::Player2 => MapTile::Player1,
+                                other => *other,
+                            };
+                            assert_eq!(map.tiles[my][mx], expected, "{symmetry:?} seed {seed}");
+                        }
+                    }
+                    assert!(map.count(MapTile::Player1) > 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn density_has_to_leave_room() {
+        for density in [0.0, 0.99] {
+            assert!(
+                generate(&args(Symmetry::Mirror, density), 0).is_ok(),
+                "{density}"
+            );
+        }
+        for density in [-0.1, 1.0] {
+            let err = generate(&args(Symmetry::Mirror, density), 0).unwrap_err();
+            assert_eq!(err, "density has to be at least 0 and less than 1");
+        }
+    }
+
+    #[test]
+    fn same_seed_same_map() {
+        let args = args(Symmetry::Rotational, 0.2);
+
+        assert_eq!(
+            generate(&args, 3).unwrap().tiles,
+            generate(&args, 3).unwrap().tiles
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use bevy::prelude::{
+    shape, AlphaMode, Assets, BuildChildren, Commands, Component, DespawnRecursiveExt, Entity,
+    Local, Mesh, Parent, PbrBundle, Query, Res, ResMut, StandardMaterial, Transform, With,
+};
+
+use crate::{
+    board::tile_color,
+    parse::{piece::PieceTile, State},
+    playback::Playback,
+    Args,
+};
+
+/// Height of the ghost slab above the top of the tiles
+const GHOST_HEIGHT: f32 = 0.2;
+
+/// Marks the see through piece showing where the answer of the shown turn goes
+#[derive(Component)]
+pub struct Ghost;
+
+/// Keeps a ghost on every game, as children of the game so they follow its board around
+pub fn draw_ghost(
+    mut commands: Commands,
+    args: Res<Args>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut shown: Local<HashMap<Entity, Option<usize>>>,
+
+    games: Query<(Entity, &State, &Playback)>,
+    ghosts: Query<(Entity, &Parent), With<Ghost>>,
+) {
+    for (game, state, playback) in games.iter() {
+        // the board of a turn is printed before its answer, so the piece isn't on it yet
+        let turn = state
+            .turns
+            .get(playback.turn)
+            .and_then(|turn| Some((turn, turn.answer.answer?, turn.answer.tile()?)));
+        let wanted = turn.map(|_| playback.turn);
+
+        if shown.get(&game) == Some(&wanted) {
+            continue;
+        }
+        shown.insert(game, wanted);
+
+        for (entity, parent) in ghosts.iter() {
+            if parent.get() == game {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+
+        let Some((turn, (x, y), tile)) = turn else {
+            continue;
+        };
+
+        let mesh = meshes.add(Mesh::from(shape::Box::new(1.0, GHOST_HEIGHT, 1.0)));
+        let material = materials.add(StandardMaterial {
+            base_color: *tile_color(tile, &args).set_a(0.5),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..Default::default()
+        });
+
+        commands.entity(game).with_children(|parent| {
+            for (py, row) in turn.piece.tiles.iter().enumerate() {
+                for (px, piece_tile) in row.iter().enumerate() {
+                    if *piece_tile != PieceTile::Piece {
+                        continue;
+                    }
+                    let (Some(x), Some(y)) = (x.checked_add(px), y.checked_add(py)) else {
+                        continue;
+                    };
+
+                    parent.spawn((
+                        PbrBundle {
+                            mesh: mesh.clone(),
+                            material: material.clone(),
+                            transform: Transform::from_xyz(x as f32, 0.5 + GHOST_HEIGHT, y as f32),
+                            ..Default::default()
+                        },
+                        Ghost,
+                    ));
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,315 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use bevy::prelude::{
+    Commands, Entity, EventReader, EventWriter, Input, KeyCode, Query, Res, ResMut, Resource, With,
+};
+use bevy_egui::{egui, EguiContext};
+use serde::Deserialize;
+
+use crate::{
+    camera::CameraMode,
+    draw::Panels,
+    games::{next_game, Focused, Game, GameView},
+    labels::AxisLabels,
+    parse::State,
+    playback::Playback,
+};
+
+/// Everything a key can be bound to
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    PlayPause,
+    StepForward,
+    StepBack,
+    JumpToStart,
+    JumpToEnd,
+    TogglePanels,
+    SwitchCamera,
+    Screenshot,
+    ToggleLabels,
+    ToggleHelp,
+    NextGame,
+    ToggleGrid,
+    ToggleOverlay,
+}
+
+impl Action {
+    const ALL: [Action; 13] = [
+        Action::PlayPause,
+        Action::StepForward,
+        Action::StepBack,
+        Action::JumpToStart,
+        Action::JumpToEnd,
+        Action::TogglePanels,
+        Action::SwitchCamera,
+        Action::Screenshot,
+        Action::ToggleLabels,
+        Action::ToggleHelp,
+        Action::NextGame,
+        Action::ToggleGrid,
+        Action::ToggleOverlay,
+    ];
+
+    fn description(self) -> &'static str {
+        match self {
+            Action::PlayPause => "Play or pause",
+            Action::StepForward => "Next turn",
+            Action::StepBack => "Previous turn",
+            Action::JumpToStart => "First turn",
+            Action::JumpToEnd => "Newest turn",
+            Action::TogglePanels => "Show or hide the panels",
+            Action::SwitchCamera => "Switch between fly and top down camera",
+            Action::Screenshot => "Save a screenshot",
+            Action::ToggleLabels => "Show or hide the axis labels",
+            Action::ToggleHelp => "Show or hide this help",
+            Action::NextGame => "Focus the next game",
+            Action::ToggleGrid => "Switch between tabs and a grid of every game",
+            Action::ToggleOverlay => "Show or hide the overlay",
+        }
+    }
+}
+
+/// Keys for every action, the flycam keeps WASD, space, shift and escape for itself
+#[derive(Resource, Debug, Clone)]
+pub struct KeyBindings(HashMap<Action, KeyCode>);
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings(HashMap::from([
+            (Action::PlayPause, KeyCode::P),
+            (Action::StepForward, KeyCode::Right),
+            (Action::StepBack, KeyCode::Left),
+            (Action::JumpToStart, KeyCode::Home),
+            (Action::JumpToEnd, KeyCode::End),
+            (Action::TogglePanels, KeyCode::Tab),
+            (Action::SwitchCamera, KeyCode::C),
+            (Action::Screenshot, KeyCode::F12),
+            (Action::ToggleLabels, KeyCode::L),
+            (Action::ToggleHelp, KeyCode::H),
+            (Action::NextGame, KeyCode::N),
+            (Action::ToggleGrid, KeyCode::G),
+            (Action::ToggleOverlay, KeyCode::O),
+        ]))
+    }
+}
+
+impl KeyBindings {
+    /// Reads a json object of action names to key names, like `{ "play_pause": "Return" }`,
+    /// actions it leaves out keep their default key
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::from_json(&text)
+    }
+
+    fn from_json(text: &str) -> Result<Self, String> {
+        let overrides: HashMap<Action, String> =
+            serde_json::from_str(text).map_err(|e| e.to_string())?;
+
+        let mut bindings = KeyBindings::default();
+        for (action, name) in overrides {
+            let key = key_from_name(&name).ok_or(format!("unknown key {name:?}"))?;
+            bindings.0.insert(action, key);
+        }
+
+        Ok(bindings)
+    }
+}
+
+/// Whether the help overlay listing the key bindings is open
+#[derive(Resource, Default, Debug)]
+pub struct Help {
+    pub shown: bool,
+}
+
+pub fn read_keys(
+    keys: Res<Input<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut egui_context: ResMut<EguiContext>,
+    mut actions: EventWriter<Action>,
+) {
+    // typing into an egui field shouldn't also control the visualizer
+    if egui_context.ctx_mut().wants_keyboard_input() {
+        return;
+    }
+
+    for action in Action::ALL {
+        if bindings
+            .0
+            .get(&action)
+            .is_some_and(|key| keys.just_pressed(*key))
+        {
+            actions.send(action);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn apply_actions(
+    mut commands: Commands,
+    mut actions: EventReader<Action>,
+    mut panels: ResMut<Panels>,
+    mut labels: ResMut<AxisLabels>,
+    mut camera: ResMut<CameraMode>,
+    mut view: ResMut<GameView>,
+    mut help: ResMut<Help>,
+
+    mut focused: Query<(&State, &mut Playback), With<Focused>>,
+    games: Query<(Entity, &Game, Option<&Focused>)>,
+) {
+    let mut focused = focused.get_single_mut().ok();
+
+    for action in actions.iter() {
+        // the playback keys move the focused game
+        if let Some((state, playback)) = &mut focused {
+            let count = state.board_count();
+
+            match action {
+                Action::PlayPause => playback.paused = !playback.paused,
+                Action::StepForward => playback.step(1, count),
+                Action::StepBack => playback.step(-1, count),
+                Action::JumpToStart => {
+                    playback.paused = true;
+                    playback.seek(0, count);
+                }
+                Action::JumpToEnd => playback.jump_to_live(count),
+                _ => {}
+            }
+        }
+
+        match action {
+            Action::TogglePanels => panels.shown = !panels.shown,
+            Action::SwitchCamera => camera.switch(),
+            Action::ToggleLabels => labels.enabled = !labels.enabled,
+            Action::ToggleHelp => help.shown = !help.shown,
+            Action::NextGame => next_game(
+                &mut commands,
+                games
+                    .iter()
+                    .map(|(entity, game, focused)| (entity, game.index, focused.is_some())),
+            ),
+            Action::ToggleGrid => view.switch(),
+            // the screenshot is taken where the camera is and the overlays toggle themselves,
+            // the playback keys are handled above
+            _ => {}
+        }
+    }
+}
+
+pub fn draw_help(
+    bindings: Res<KeyBindings>,
+    mut help: ResMut<Help>,
+    mut egui_context: ResMut<EguiContext>,
+) {
+    if !help.shown {
+        return;
+    }
+
+    let mut open = true;
+    egui::Window::new("Keys")
+        .open(&mut open)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(egui_context.ctx_mut(), |ui| {
+            egui::Grid::new("keys").striped(true).show(ui, |ui| {
+                for action in Action::ALL {
+                    let key = bindings.0.get(&action).map_or("", |key| key_name(*key));
+                    ui.monospace(key);
+                    ui.label(action.description());
+                    ui.end_row();
+                }
+
+                ui.monospace("WASD");
+                ui.label("Move the fly camera");
+                ui.end_row();
+                ui.monospace("Escape");
+                ui.label("Grab or release the cursor");
+                ui.end_row();
+            });
+        });
+
+    if !open {
+        help.shown = false;
+    }
+}
+
+macro_rules! key_names {
+    ($($key:ident),* $(,)?) => {
+        fn key_from_name(name: &str) -> Option<KeyCode> {
+            match name {
+                $(stringify!($key) => Some(KeyCode::$key),)*
+                _ => None,
+            }
+        }
+
+        fn key_name(key: KeyCode) -> &'static str {
+            match key {
+                $(KeyCode::$key => stringify!($key),)*
+                _ => "?",
+            }
+        }
+    };
+}
+
+key_names!(
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, Key0, Key1, Key2,
+    Key3, Key4, Key5, Key6, Key7, Key8, Key9, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+    Escape, Home, End, PageUp, PageDown, Insert, Delete, Left, Up, Right, Down, Back, Return,
+    Space, Tab, Comma, Period, Slash, Minus, Equals,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_action_has_a_default_key() {
+        let bindings = KeyBindings::default();
+
+        for action in Action::ALL {
+            assert!(bindings.0.contains_key(&action), "{action:?}");
+        }
+    }
+
+    #[test]
+    fn bindings_parse_by_action_name() {
+        let bindings = KeyBindings::from_json(
+            r#"{ "play_pause": "Return", "toggle_grid": "F2", "toggle_overlay": "V" }"#,
+        )
+        .unwrap();
+
+        assert_eq!(bindings.0[&Action::PlayPause], KeyCode::Return);
+        assert_eq!(bindings.0[&Action::ToggleGrid], KeyCode::F2);
+        assert_eq!(bindings.0[&Action::ToggleOverlay], KeyCode::V);
+        // left out actions keep their default
+        assert_eq!(bindings.0[&Action::NextGame], KeyCode::N);
+    }
+
+    #[test]
+    fn every_action_name_parses() {
+        for name in [
+            "play_pause",
+            "step_forward",
+            "step_back",
+            "jump_to_start",
+            "jump_to_end",
+            "toggle_panels",
+            "switch_camera",
+            "screenshot",
+            "toggle_labels",
+            "toggle_help",
+            "next_game",
+            "toggle_grid",
+            "toggle_overlay",
+        ] {
+            let json = format!(r#"{{ "{name}": "K" }}"#);
+            let bindings = KeyBindings::from_json(&json).unwrap();
+            assert!(bindings.0.values().any(|key| *key == KeyCode::K), "{name}");
+        }
+    }
+
+    #[test]
+    fn unknown_names_are_errors() {
+        assert!(KeyBindings::from_json(r#"{ "fly_away": "K" }"#).is_err());
+        assert!(KeyBindings::from_json(r#"{ "toggle_overlay": "Hyper" }"#).is_err());
+    }
+}
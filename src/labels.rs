@@ -0,0 +1,117 @@
+use bevy::{
+    prelude::{Camera, GlobalTransform, Query, Res, ResMut, Resource, Vec3, With},
+    window::Windows,
+};
+use bevy_egui::{
+    egui::{Align2, Color32, FontId, LayerId, Pos2, Stroke},
+    EguiContext,
+};
+
+use crate::{board::Board, games::Focused};
+
+/// Spacings the labels snap to, so they stay on round numbers
+const STEPS: [usize; 7] = [1, 2, 5, 10, 20, 50, 100];
+
+/// Closest two labels are allowed to get on screen, in pixels
+const MIN_LABEL_DISTANCE: f32 = 24.0;
+
+/// Column and row numbers around the board, numbered like the engine answers
+#[derive(Resource, Debug)]
+pub struct AxisLabels {
+    pub enabled: bool,
+}
+
+impl Default for AxisLabels {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+pub fn draw_axis_labels(
+    labels: Res<AxisLabels>,
+    windows: Res<Windows>,
+    mut egui_context: ResMut<EguiContext>,
+
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    boards: Query<(&Board, &GlobalTransform), With<Focused>>,
+) {
+    if !labels.enabled {
+        return;
+    }
+
+    let (Some(window), Some((camera, camera_transform)), Ok((board, board_transform))) = (
+        windows.get_primary(),
+        cameras.iter().find(|(camera, _)| camera.is_active),
+        boards.get_single(),
+    ) else {
+        return;
+    };
+
+    // bevy puts the viewport origin in the bottom left corner and egui in the top left,
+    // the positions below are on the board and moved to wherever the board is laid out
+    let to_screen = |local: Vec3| {
+        camera
+            .world_to_viewport(camera_transform, board_transform.transform_point(local))
+            .map(|p| Pos2::new(p.x, window.height() - p.y))
+    };
+
+    // labels sit on the top face of the tiles, half a tile outside the board
+    let top = 0.5;
+    let step = spacing(
+        to_screen(Vec3::new(0.0, top, 0.0)),
+        to_screen(Vec3::X * 1.0 + Vec3::Y * top),
+    );
+
+    let painter = egui_context.ctx_mut().layer_painter(LayerId::background());
+    let font = FontId::monospace(12.0);
+    let grid = Stroke::new(1.0, Color32::from_rgba_unmultiplied(128, 128, 128, 96));
+    let (width, heigth) = (board.width as f32, board.heigth as f32);
+
+    for column in (0..board.width).step_by(step) {
+        let x = column as f32;
+
+        if let Some(pos) = to_screen(Vec3::new(x, top, -1.2)) {
+            painter.text(
+                pos,
+                Align2::CENTER_CENTER,
+                column,
+                font.clone(),
+                Color32::GRAY,
+            );
+        }
+
+        if let (Some(start), Some(end)) = (
+            to_screen(Vec3::new(x - 0.5, top + 0.01, -0.5)),
+            to_screen(Vec3::new(x - 0.5, top + 0.01, heigth - 0.5)),
+        ) {
+            painter.line_segment([start, end], grid);
+        }
+    }
+
+    for row in (0..board.heigth).step_by(step) {
+        let y = row as f32;
+
+        if let Some(pos) = to_screen(Vec3::new(-1.2, top, y)) {
+            painter.text(pos, Align2::CENTER_CENTER, row, font.clone(), Color32::GRAY);
+        }
+
+        if let (Some(start), Some(end)) = (
+            to_screen(Vec3::new(-0.5, top + 0.01, y - 0.5)),
+            to_screen(Vec3::new(width - 0.5, top + 0.01, y - 0.5)),
+        ) {
+            painter.line_segment([start, end], grid);
+        }
+    }
+}
+
+/// Picks the smallest step that keeps neighbouring labels apart on screen
+fn spacing(first: Option<Pos2>, second: Option<Pos2>) -> usize {
+    let Some(distance) = first.zip(second).map(|(a, b)| a.distance(b)) else {
+        return STEPS[STEPS.len() - 1];
+    };
+
+    STEPS
+        .into_iter()
+        .find(|step| *step as f32 * distance >= MIN_LABEL_DISTANCE)
+        .unwrap_or(STEPS[STEPS.len() - 1])
+}
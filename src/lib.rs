@@ -0,0 +1,109 @@
+#![feature(option_result_contains)]
+
+#[macro_use]
+extern crate lazy_static;
+
+use bevy::prelude::{Component, Resource};
+use board::{Animation, ClaimView};
+use clap::{Parser, Subcommand};
+use color::Rgb;
+use compare::CompareArgs;
+use export::ExportArgs;
+use genmap::GenMapArgs;
+use parse::listen::Listen;
+use std::path::PathBuf;
+
+pub mod analysis;
+pub mod board;
+pub mod camera;
+pub mod color;
+pub mod compare;
+pub mod cursor;
+pub mod draw;
+pub mod export;
+pub mod games;
+pub mod genmap;
+pub mod ghost;
+pub mod keys;
+pub mod labels;
+pub mod overlay;
+pub mod parse;
+pub mod picking;
+pub mod playback;
+pub mod screenshot;
+pub mod setup;
+
+/// Usage: ./game_engine -f ./map00 -p1 ./filler -p2 ./bender | cargo run
+#[derive(Parser, Resource, Clone, Debug, Component)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Debug prints info it recieves
+    #[arg(short, long, default_value_t = false)]
+    pub debug: bool,
+
+    /// Makes it grab cursor when you click
+    #[arg(short, long, default_value_t = false)]
+    pub grab_cursor: bool,
+
+    /// Shows a map file instead of reading the game from stdin
+    #[arg(short, long)]
+    pub map: Option<PathBuf>,
+
+    /// Shows a finished game, a transcript or an exported json replay, can be given more than once
+    #[arg(short, long)]
+    pub file: Vec<PathBuf>,
+
+    /// Reads games from tcp://HOST:PORT or unix:/PATH instead of stdin, can be given more than once,
+    /// every connection is a game of its own
+    #[arg(long, conflicts_with = "map")]
+    pub listen: Vec<Listen>,
+
+    /// How newly claimed tiles appear
+    #[arg(long, value_enum, default_value_t = Animation::Drop)]
+    pub animation: Animation,
+
+    /// How long placing a tile takes, in milliseconds
+    #[arg(long, default_value_t = 300)]
+    pub animation_duration: u64,
+
+    /// Shows when tiles were claimed through their height or color
+    #[arg(long, value_enum, default_value_t = ClaimView::None)]
+    pub claim_view: ClaimView,
+
+    /// Turns it takes for the claim view to reach its tallest or lightest tiles
+    #[arg(long, default_value_t = 100)]
+    pub claim_scale: usize,
+
+    /// Turns shown per second, 0 shows every turn as soon as it arrives
+    #[arg(short, long, default_value_t = 10.0)]
+    pub speed: f32,
+
+    /// Color of player 1 tiles
+    #[arg(long, global = true, default_value = "#ff0000")]
+    pub player1_color: Rgb,
+
+    /// Color of player 2 tiles
+    #[arg(long, global = true, default_value = "#0000ff")]
+    pub player2_color: Rgb,
+
+    /// Json file mapping actions to keys, like {"play_pause": "Return"}
+    #[arg(long)]
+    pub keys: Option<PathBuf>,
+
+    /// Folder screenshots are saved in
+    #[arg(long, default_value = ".")]
+    pub screenshot_dir: PathBuf,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum Command {
+    /// Generates a random map file
+    Genmap(GenMapArgs),
+    /// Renders a finished game to images without opening a window
+    Export(ExportArgs),
+    /// Shows two games of the same map and seed side by side, turn by turn
+    Compare(CompareArgs),
+}
@@ -1,70 +1,122 @@
-#![feature(option_result_contains)]
-
-#[macro_use]
-extern crate lazy_static;
-
-use crate::parse::parse;
-use crate::parse::State;
-use crate::setup::setup;
 use bevy::prelude::*;
 use bevy_egui::EguiPlugin;
 use bevy_flycam::PlayerPlugin;
 use clap::Parser;
-use cursor::cursor_grab_system;
-use cursor::should_grab_cursor;
-use draw::draw_cubes;
-use draw::draw_info;
-use draw::finish_drawing;
-use draw::finished_parsing;
-use parse::win::game_hasnt_finished;
+use filler_visualizer::analysis::mobility::{analyse_mobility, draw_mobility};
+use filler_visualizer::analysis::moves::{analyse_moves, draw_analysis, Heuristics};
+use filler_visualizer::board::animate_board;
+use filler_visualizer::camera::{spawn_top_down_camera, switch_camera, CameraMode};
+use filler_visualizer::compare::{draw_comparison, sync_playback};
+use filler_visualizer::cursor::cursor_grab_system;
+use filler_visualizer::cursor::should_grab_cursor;
+use filler_visualizer::draw::draw_board;
+use filler_visualizer::draw::draw_info;
+use filler_visualizer::draw::draw_move_log;
+use filler_visualizer::draw::draw_stream_end;
+use filler_visualizer::draw::draw_win;
+use filler_visualizer::draw::Panels;
+use filler_visualizer::games::{accept_games, draw_games, layout_games, GameView};
+use filler_visualizer::ghost::draw_ghost;
+use filler_visualizer::keys::{apply_actions, draw_help, read_keys, Action, Help, KeyBindings};
+use filler_visualizer::labels::{draw_axis_labels, AxisLabels};
+use filler_visualizer::overlay::{draw_overlay_panel, draw_overlays, toggle_overlay, Overlays};
+use filler_visualizer::parse::parse;
+use filler_visualizer::picking::{draw_tile_info, pick_tile, Picking};
+use filler_visualizer::playback::{advance_playback, draw_playback};
+use filler_visualizer::screenshot::ScreenshotPlugin;
+use filler_visualizer::setup::{setup, Startup};
+use filler_visualizer::{export, genmap, Args, Command};
 
-pub mod cursor;
-pub mod draw;
-pub mod parse;
-pub mod setup;
+fn main() {
+    let args = Args::parse();
 
-/// Usage: ./game_engine -f ./map00 -p1 ./filler -p2 ./bender | cargo run
-#[derive(Parser, Resource, Default, Clone, Debug, Component)]
-#[command(author, version, about, long_about = None)]
-pub struct Args {
-    /// Debug prints info it recieves
-    #[arg(short, long, default_value_t = false)]
-    debug: bool,
+    let result = match &args.command {
+        Some(Command::Genmap(genmap_args)) => Some(genmap::genmap(genmap_args)),
+        Some(Command::Export(export_args)) => Some(export::export(&args, export_args)),
+        // compare opens the window with both games side by side
+        Some(Command::Compare(_)) | None => None,
+    };
 
-    /// Makes it grab cursor when you click
-    #[arg(short, long, default_value_t = false)]
-    grab_cursor: bool,
-}
+    if let Some(result) = result {
+        if let Err(err) = result {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+
+        return;
+    }
+
+    let bindings = match &args.keys {
+        Some(path) => KeyBindings::load(path).unwrap_or_else(|err| {
+            eprintln!("Couldn't load key bindings from {}: {err}", path.display());
+            std::process::exit(1);
+        }),
+        None => KeyBindings::default(),
+    };
+
+    // bad paths and addresses are reported here, before the window opens
+    let startup = Startup::load(&args).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(1);
+    });
 
-fn main() {
     App::new()
         .insert_resource(ClearColor(Color::rgb(0.0, 0.0, 0.0)))
         .insert_resource(AmbientLight {
             color: Color::WHITE,
             brightness: 0.5,
         })
-        .init_resource::<State>()
-        .init_resource::<Args>()
+        .init_resource::<Picking>()
+        .init_resource::<AxisLabels>()
+        .init_resource::<Panels>()
+        .init_resource::<CameraMode>()
+        .init_resource::<Help>()
+        .init_resource::<GameView>()
+        .init_resource::<Heuristics>()
+        .init_resource::<Overlays>()
+        .insert_resource(bindings)
+        .insert_resource(startup)
+        .add_event::<Action>()
+        .insert_resource(args)
         .add_plugins(DefaultPlugins)
         .add_plugin(PlayerPlugin)
         .add_plugin(EguiPlugin)
+        .add_plugin(ScreenshotPlugin)
         .add_startup_system(setup)
+        .add_startup_system(spawn_top_down_camera)
         .add_system_set(
             SystemSet::new()
                 .with_run_criteria(should_grab_cursor)
                 .with_system(cursor_grab_system),
         )
-        .add_system_set(
-            SystemSet::new()
-                .with_run_criteria(game_hasnt_finished)
-                .with_system(parse),
-        )
+        .add_system(accept_games)
+        .add_system(parse.after(accept_games))
+        .add_system(advance_playback.after(parse))
+        .add_system(draw_playback)
         .add_system(draw_info)
-        .add_system_set(
-            SystemSet::new()
-                .with_run_criteria(finished_parsing)
-                .with_system(draw_cubes)
-                .with_system(finish_drawing),
-        )
+        .add_system(sync_playback.after(advance_playback).after(apply_actions))
+        .add_system(draw_comparison)
+        .add_system(analyse_moves.after(parse))
+        .add_system(draw_analysis)
+        .add_system(analyse_mobility.after(parse))
+        .add_system(draw_mobility)
+        .add_system(toggle_overlay.after(read_keys))
+        .add_system(draw_overlays.after(draw_board).after(toggle_overlay))
+        .add_system(draw_overlay_panel)
+        .add_system(draw_board.after(sync_playback))
+        .add_system(draw_win)
+        .add_system(draw_move_log)
+        .add_system(draw_ghost.after(advance_playback))
+        .add_system(draw_stream_end)
+        .add_system(animate_board.after(draw_board))
+        .add_system(draw_axis_labels)
+        .add_system(pick_tile)
+        .add_system(draw_tile_info.after(pick_tile))
+        .add_system(read_keys)
+        .add_system(apply_actions.after(read_keys).before(advance_playback))
+        .add_system(switch_camera.after(apply_actions))
+        .add_system(draw_help)
+        .add_system(draw_games)
+        .add_system(layout_games.after(draw_board).after(apply_actions))
         .run();
 }
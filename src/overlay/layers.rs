@@ -0,0 +1,211 @@
+use bevy::prelude::Color;
+
+use crate::{
+    analysis::{neighbours, placements},
+    parse::{map::MapTile, piece::PieceTile, State},
+    Args,
+};
+
+use super::{ColorRamp, Layer};
+
+/// Empty cells next to both players, where the next pieces will fight
+pub struct FrontierLayer;
+
+impl Layer for FrontierLayer {
+    fn name(&self) -> &'static str {
+        "Frontier"
+    }
+
+    fn description(&self) -> &'static str {
+        "Empty cells next to both players, brighter the more player tiles they touch"
+    }
+
+    fn values(&self, state: &State, board: usize) -> Vec<Option<f32>> {
+        let Some(map) = state.board(board) else {
+            return Vec::new();
+        };
+
+        (0..map.heigth)
+            .flat_map(|y| (0..map.width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                if map.tiles[y][x] != MapTile::Empty {
+                    return None;
+                }
+
+                let touching = |tile| {
+                    neighbours(map, x, y)
+                        .filter(|(nx, ny)| map.tiles[*ny][*nx] == tile)
+                        .count()
+                };
+                let (player1, player2) = (touching(MapTile::Player1), touching(MapTile::Player2));
+
+                (player1 > 0 && player2 > 0).then_some((player1 + player2) as f32)
+            })
+            .collect()
+    }
+
+    fn ramp(&self, _: &Args) -> ColorRamp {
+        ColorRamp(vec![Color::rgb(0.6, 0.3, 0.0), Color::rgb(1.0, 0.8, 0.0)])
+    }
+
+    fn range(&self, _: &[Option<f32>]) -> (f32, f32) {
+        // two tiles is the least a frontier cell touches, four the most
+        (2.0, 4.0)
+    }
+}
+
+/// How many legal placements of the piece of the turn cover every cell
+pub struct PlacementLayer;
+
+impl Layer for PlacementLayer {
+    fn name(&self) -> &'static str {
+        "Placement frequency"
+    }
+
+    fn description(&self) -> &'static str {
+        "How many legal placements of the piece of the shown turn cover each cell"
+    }
+
+    fn values(&self, state: &State, board: usize) -> Vec<Option<f32>> {
+        // the final board has no piece to place
+        let Some((turn, tile)) = state
+            .turns
+            .get(board)
+            .and_then(|turn| Some((turn, turn.answer.tile()?)))
+        else {
+            return Vec::new();
+        };
+        let map = &turn.map;
+
+        let mut covered = vec![0; map.width * map.heigth];
+        for (x, y) in placements(map, &turn.piece, tile) {
+            for (py, row) in turn.piece.tiles.iter().enumerate() {
+                for (px, piece_tile) in row.iter().enumerate() {
+                    if *piece_tile == PieceTile::Piece {
+                        covered[(y + py) * map.width + x + px] += 1;
+                    }
+                }
+            }
+        }
+
+        covered
+            .into_iter()
+            .map(|count| (count > 0).then_some(count as f32))
+            .collect()
+    }
+
+    fn ramp(&self, _: &Args) -> ColorRamp {
+        ColorRamp(vec![
+            Color::rgb(0.1, 0.1, 0.5),
+            Color::rgb(0.0, 0.7, 0.7),
+            Color::rgb(1.0, 1.0, 0.3),
+        ])
+    }
+
+    fn range(&self, values: &[Option<f32>]) -> (f32, f32) {
+        let high = values
+            .iter()
+            .flatten()
+            .fold(1.0, |high: f32, v| high.max(*v));
+        (1.0, high)
+    }
+}
+
+/// Board every tile was claimed on, early tiles dark and the newest bright
+pub struct ClaimTurnLayer;
+
+impl Layer for ClaimTurnLayer {
+    fn name(&self) -> &'static str {
+        "Claim turn"
+    }
+
+    fn description(&self) -> &'static str {
+        "When each tile was claimed, from the starting tiles to the shown turn"
+    }
+
+    fn values(&self, state: &State, board: usize) -> Vec<Option<f32>> {
+        let Some(map) = state.board(board) else {
+            return Vec::new();
+        };
+
+        (0..map.heigth)
+            .flat_map(|y| (0..map.width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                // tiles claimed after the shown board aren't on it yet
+                state
+                    .claims
+                    .get(x, y)
+                    .filter(|claimed| *claimed <= board)
+                    .map(|claimed| claimed as f32)
+            })
+            .collect()
+    }
+
+    fn ramp(&self, _: &Args) -> ColorRamp {
+        ColorRamp(vec![
+            Color::rgb(0.2, 0.0, 0.4),
+            Color::rgb(0.9, 0.2, 0.3),
+            Color::rgb(1.0, 0.9, 0.6),
+        ])
+    }
+
+    fn range(&self, values: &[Option<f32>]) -> (f32, f32) {
+        let high = values
+            .iter()
+            .flatten()
+            .fold(0.0, |high: f32, v| high.max(*v));
+        (0.0, high)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        analysis::tests::{map, piece},
+        parse::{answer::Answer, turn::Turn},
+    };
+
+    use super::*;
+
+    fn game(rows: &[&str], piece_rows: &[&str]) -> State {
+        State {
+            turns: vec![Turn {
+                map: map(rows),
+                piece: piece(piece_rows),
+                answer: Answer {
+                    player: "@".to_string(),
+                    answer: Some((0, 0)),
+                },
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn frontier_between_both_players() {
+        // (1, 0) touches both players, (0, 1) only player 1
+        let state = game(&["@.$", "..."], &["O"]);
+
+        assert_eq!(
+            FrontierLayer.values(&state, 0),
+            vec![None, Some(2.0), None, None, None, None]
+        );
+    }
+
+    #[test]
+    fn placements_counted_per_cell() {
+        // the domino goes to the right of or below player 1
+        let state = game(&["@..", "..$"], &["OO"]);
+
+        assert_eq!(
+            PlacementLayer.values(&state, 0),
+            vec![Some(1.0), Some(1.0), None, None, None, None]
+        );
+
+        let state = game(&["@..", "..$"], &["O", "O"]);
+        assert_eq!(
+            PlacementLayer.values(&state, 0),
+            vec![Some(1.0), None, None, Some(1.0), None, None]
+        );
+    }
+}
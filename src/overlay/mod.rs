@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+
+use bevy::{
+    prelude::{
+        AlphaMode, Assets, BuildChildren, Color, Commands, Component, DespawnRecursiveExt, Entity,
+        EventReader, Local, Mesh, Parent, PbrBundle, Query, Res, ResMut, Resource,
+        StandardMaterial, With,
+    },
+    render::{mesh::Indices, render_resource::PrimitiveTopology},
+};
+use bevy_egui::{egui, EguiContext};
+
+use crate::{
+    analysis::mobility::ReachLayer, board::Board, draw::Panels, keys::Action, parse::State, Args,
+};
+
+use self::layers::{ClaimTurnLayer, FrontierLayer, PlacementLayer};
+
+pub mod layers;
+
+/// How far the overlay floats above the top of each tile
+const OVERLAY_GAP: f32 = 0.02;
+
+/// How see through the overlay colors are
+const OVERLAY_ALPHA: f32 = 0.6;
+
+/// Something worth knowing about every cell of a board, drawn over it as a heatmap
+pub trait Layer: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// What the values mean, shown next to the toggle
+    fn description(&self) -> &'static str;
+
+    /// Value of every cell of a board of the game, counted like `State::board`, by
+    /// `y * width + x`. Cells without a value are left uncovered
+    fn values(&self, state: &State, board: usize) -> Vec<Option<f32>>;
+
+    /// Colors from the lowest value to the highest
+    fn ramp(&self, args: &Args) -> ColorRamp;
+
+    /// Values mapped to the ends of the ramp, by default the lowest and highest on the board
+    fn range(&self, values: &[Option<f32>]) -> (f32, f32) {
+        values
+            .iter()
+            .flatten()
+            .fold((f32::MAX, f32::MIN), |(low, high), v| {
+                (low.min(*v), high.max(*v))
+            })
+    }
+}
+
+/// Colors spread evenly over the values of a layer, blended in between
+#[derive(Debug, Clone)]
+pub struct ColorRamp(pub Vec<Color>);
+
+impl ColorRamp {
+    /// Color at `t` between 0 and 1
+    pub fn color(&self, t: f32) -> Color {
+        let stops = &self.0;
+        if stops.len() < 2 {
+            return stops.first().copied().unwrap_or(Color::WHITE);
+        }
+
+        let position = t.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+        let i = (position.floor() as usize).min(stops.len() - 2);
+        let (a, b) = (stops[i], stops[i + 1]);
+        let f = position - i as f32;
+
+        Color::rgba(
+            a.r() + (b.r() - a.r()) * f,
+            a.g() + (b.g() - a.g()) * f,
+            a.b() + (b.b() - a.b()) * f,
+            a.a() + (b.a() - a.a()) * f,
+        )
+    }
+}
+
+/// Every layer that can be drawn and which one is, only one at a time so they don't cover
+/// each other
+#[derive(Resource)]
+pub struct Overlays {
+    pub layers: Vec<Box<dyn Layer>>,
+    pub shown: Option<usize>,
+    /// Layer the toggle key brings back
+    last: usize,
+}
+
+impl Overlays {
+    /// Hides the shown layer, or shows the one hidden last
+    pub fn toggle(&mut self) {
+        self.shown = match self.shown {
+            Some(layer) => {
+                self.last = layer;
+                None
+            }
+            None => Some(self.last.min(self.layers.len().saturating_sub(1))),
+        };
+    }
+}
+
+impl Default for Overlays {
+    fn default() -> Self {
+        Overlays {
+            layers: vec![
+                Box::new(FrontierLayer),
+                Box::new(PlacementLayer),
+                Box::new(ClaimTurnLayer),
+                Box::new(ReachLayer),
+            ],
+            shown: None,
+            last: 0,
+        }
+    }
+}
+
+/// Marks a flat mesh of colored cells drawn over a board
+#[derive(Component)]
+pub struct Overlay;
+
+/// One flat square per cell that has a color, tile (x, y) is covered at (x, y) like the board
+/// and just above the height `top` gives for it
+pub fn overlay_mesh(
+    width: usize,
+    heigth: usize,
+    top: impl Fn(usize, usize) -> f32,
+    color: impl Fn(usize, usize) -> Option<Color>,
+) -> Mesh {
+    let mut positions = Vec::new();
+    let mut colors = Vec::new();
+    let mut indices = Vec::new();
+
+    for y in 0..heigth {
+        for x in 0..width {
+            let Some(c) = color(x, y) else {
+                continue;
+            };
+
+            let first = positions.len() as u32;
+            let height = top(x, y) + OVERLAY_GAP;
+            let (x, z) = (x as f32, y as f32);
+            positions.extend([
+                [x - 0.5, height, z - 0.5],
+                [x - 0.5, height, z + 0.5],
+                [x + 0.5, height, z + 0.5],
+                [x + 0.5, height, z - 0.5],
+            ]);
+            colors.extend([c.as_linear_rgba_f32(); 4]);
+            indices.extend([first, first + 1, first + 2, first, first + 2, first + 3]);
+        }
+    }
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_NORMAL,
+        vec![[0.0, 1.0, 0.0]; positions.len()],
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.set_indices(Some(Indices::U32(indices)));
+
+    mesh
+}
+
+/// Draws the shown layer over the board of every game, as a child so it follows the board around
+#[allow(clippy::too_many_arguments)]
+pub fn draw_overlays(
+    mut commands: Commands,
+    overlays: Res<Overlays>,
+    args: Res<Args>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut shown: Local<HashMap<Entity, Option<(usize, usize)>>>,
+
+    games: Query<(Entity, &State, &Board)>,
+    children: Query<(Entity, &Parent), With<Overlay>>,
+) {
+    for (game, state, board) in games.iter() {
+        // follows the board rather than the playback, so the overlay never shows another turn
+        let wanted = overlays.shown.map(|layer| (layer, board.turn));
+        if shown.get(&game) == Some(&wanted) {
+            continue;
+        }
+        shown.insert(game, wanted);
+
+        for (entity, parent) in children.iter() {
+            if parent.get() == game {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+
+        let (Some(layer), Some(map)) = (
+            wanted.and_then(|(layer, _)| overlays.layers.get(layer)),
+            state.board(board.turn),
+        ) else {
+            continue;
+        };
+
+        let values = layer.values(state, board.turn);
+        let ramp = layer.ramp(&args);
+        let (low, high) = layer.range(&values);
+
+        // extruded claims would hide an overlay at one height, every square sits on its tile
+        let top = |x, y| board.top(x, y, &args);
+        let mesh = overlay_mesh(map.width, map.heigth, top, |x, y| {
+            let value = values.get(y * map.width + x).copied().flatten()?;
+            // a board where every value is the same gets the top of the ramp
+            let t = if high > low {
+                (value - low) / (high - low)
+            } else {
+                1.0
+            };
+
+            Some(ramp.color(t))
+        });
+
+        let mesh = meshes.add(mesh);
+        // the colors come from the mesh, see through so the tiles below still show
+        let material = materials.add(StandardMaterial {
+            base_color: Color::rgba(1.0, 1.0, 1.0, OVERLAY_ALPHA),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..Default::default()
+        });
+
+        commands.entity(game).with_children(|parent| {
+            parent.spawn((
+                PbrBundle {
+                    mesh,
+                    material,
+                    ..Default::default()
+                },
+                Overlay,
+            ));
+        });
+    }
+}
+
+/// Shows or hides the overlay with its key
+pub fn toggle_overlay(mut actions: EventReader<Action>, mut overlays: ResMut<Overlays>) {
+    for action in actions.iter() {
+        if *action == Action::ToggleOverlay {
+            overlays.toggle();
+        }
+    }
+}
+
+/// Picks the layer drawn over the boards
+pub fn draw_overlay_panel(
+    panels: Res<Panels>,
+    mut overlays: ResMut<Overlays>,
+    mut egui_context: ResMut<EguiContext>,
+) {
+    if !panels.shown {
+        return;
+    }
+
+    egui::Window::new("Overlays").show(egui_context.ctx_mut(), |ui| {
+        let mut shown = overlays.shown;
+        ui.radio_value(&mut shown, None, "Off");
+
+        for (i, layer) in overlays.layers.iter().enumerate() {
+            ui.radio_value(&mut shown, Some(i), layer.name())
+                .on_hover_text(layer.description());
+        }
+
+        // only touched on a click, so the overlays aren't rebuilt every frame
+        if shown != overlays.shown {
+            overlays.shown = shown;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::render::mesh::VertexAttributeValues;
+
+    use super::*;
+
+    #[test]
+    fn ramp_ends_and_middle() {
+        let ramp = ColorRamp(vec![Color::rgb(0.0, 0.0, 0.0), Color::rgb(1.0, 0.5, 0.0)]);
+
+        assert_eq!(ramp.color(0.0), Color::rgba(0.0, 0.0, 0.0, 1.0));
+        assert_eq!(ramp.color(1.0), Color::rgba(1.0, 0.5, 0.0, 1.0));
+        assert_eq!(ramp.color(0.5), Color::rgba(0.5, 0.25, 0.0, 1.0));
+        // out of range values stick to the ends
+        assert_eq!(ramp.color(-1.0), ramp.color(0.0));
+        assert_eq!(ramp.color(2.0), ramp.color(1.0));
+    }
+
+    #[test]
+    fn ramp_middle_stop() {
+        let ramp = ColorRamp(vec![Color::RED, Color::GREEN, Color::BLUE]);
+
+        assert_eq!(ramp.color(0.5), Color::GREEN);
+        assert_eq!(ramp.color(1.0), Color::BLUE);
+    }
+
+    #[test]
+    fn toggle_brings_back_the_last_layer() {
+        let mut overlays = Overlays::default();
+
+        overlays.toggle();
+        assert_eq!(overlays.shown, Some(0));
+
+        overlays.shown = Some(2);
+        overlays.toggle();
+        assert_eq!(overlays.shown, None);
+        overlays.toggle();
+        assert_eq!(overlays.shown, Some(2));
+    }
+
+    #[test]
+    fn overlay_sits_on_each_tile() {
+        let mesh = overlay_mesh(2, 1, |x, _| x as f32, |x, _| (x == 1).then_some(Color::RED));
+
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("no positions");
+        };
+        assert_eq!(positions.len(), 4);
+        assert!(positions.iter().all(|p| p[1] == 1.0 + OVERLAY_GAP));
+    }
+}
@@ -1,6 +1,10 @@
 use std::fmt::Display;
 
-#[derive(Debug, Default, Clone)]
+use serde::{Deserialize, Serialize};
+
+use super::{map::MapTile, tile::Tile};
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Answer {
     pub player: String,
     pub answer: Option<(usize, usize)>,
@@ -18,3 +22,10 @@ impl Display for Answer {
         Ok(())
     }
 }
+
+impl Answer {
+    /// Tile of the player that answered
+    pub fn tile(&self) -> Option<MapTile> {
+        self.player.chars().next().and_then(MapTile::try_from_char)
+    }
+}
@@ -0,0 +1,58 @@
+use super::{
+    diff::MapDiff,
+    map::{Map, MapTile},
+};
+
+/// Board on which every tile was first taken, boards are counted like `State::turns`
+#[derive(Debug, Default, Clone)]
+pub struct Claims {
+    width: usize,
+    boards: Vec<Option<usize>>,
+}
+
+impl Claims {
+    pub fn get(&self, x: usize, y: usize) -> Option<usize> {
+        if x >= self.width {
+            return None;
+        }
+
+        self.boards.get(y * self.width + x).copied().flatten()
+    }
+
+    /// Records the tiles taken on a board, the first board has no diff and
+    /// every tile on it counts as taken
+    pub fn record(&mut self, board: usize, map: &Map, diff: Option<&MapDiff>) {
+        if self.width != map.width || self.boards.len() != map.width * map.heigth {
+            self.width = map.width;
+            self.boards = vec![None; map.width * map.heigth];
+        }
+
+        match diff {
+            Some(diff) => {
+                for change in &diff.changes {
+                    self.set(change.x, change.y, change.after, board);
+                }
+            }
+            None => {
+                for (y, row) in map.tiles.iter().enumerate() {
+                    for (x, tile) in row.iter().enumerate() {
+                        self.set(x, y, *tile, board);
+                    }
+                }
+            }
+        }
+    }
+
+    fn set(&mut self, x: usize, y: usize, tile: MapTile, board: usize) {
+        if x >= self.width {
+            return;
+        }
+
+        if let Some(claim) = self.boards.get_mut(y * self.width + x) {
+            *claim = match tile {
+                MapTile::Player1 | MapTile::Player2 => Some(board),
+                _ => None,
+            };
+        }
+    }
+}
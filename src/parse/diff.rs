@@ -0,0 +1,220 @@
+use std::fmt::Display;
+
+use super::{
+    map::{Map, MapTile},
+    piece::PieceTile,
+    turn::Turn,
+};
+
+/// A tile that changed owner between two boards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileChange {
+    pub x: usize,
+    pub y: usize,
+    pub before: MapTile,
+    pub after: MapTile,
+}
+
+/// Every tile that differs between two boards of the same size
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MapDiff {
+    pub changes: Vec<TileChange>,
+}
+
+impl MapDiff {
+    pub fn between(before: &Map, after: &Map) -> MapDiff {
+        let changes = after
+            .tiles
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter().enumerate().filter_map(move |(x, tile)| {
+                    let old = before.tiles.get(y).and_then(|r| r.get(x)).copied();
+                    let old = old.unwrap_or_default();
+
+                    (old != *tile).then_some(TileChange {
+                        x,
+                        y,
+                        before: old,
+                        after: *tile,
+                    })
+                })
+            })
+            .collect();
+
+        MapDiff { changes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Piece tile at the answered position that caused the change, if any
+    pub fn cause(change: &TileChange, turn: &Turn) -> Option<(usize, usize)> {
+        let (ax, ay) = turn.answer.answer?;
+        let (px, py) = (change.x.checked_sub(ax)?, change.y.checked_sub(ay)?);
+
+        let tile = turn.piece.tiles.get(py)?.get(px)?;
+        (*tile == PieceTile::Piece).then_some((px, py))
+    }
+
+    /// Checks that the diff is exactly the piece of the turn placed at its answer
+    pub fn check(&self, turn: &Turn) -> Vec<Mismatch> {
+        let mut mismatches: Vec<Mismatch> = self
+            .changes
+            .iter()
+            .filter(|c| Self::cause(c, turn).is_none())
+            .map(|c| Mismatch::Unexpected(*c))
+            .collect();
+
+        let (Some((ax, ay)), Some(tile)) = (turn.answer.answer, turn.answer.tile()) else {
+            return mismatches;
+        };
+
+        for (py, row) in turn.piece.tiles.iter().enumerate() {
+            for (px, piece_tile) in row.iter().enumerate() {
+                if *piece_tile != PieceTile::Piece {
+                    continue;
+                }
+
+                // a cell past the end of usize is off the board all the same
+                let (x, y) = (ax.saturating_add(px), ay.saturating_add(py));
+                let after = self
+                    .changes
+                    .iter()
+                    .find(|c| c.x == x && c.y == y)
+                    .map(|c| c.after)
+                    .unwrap_or_else(|| {
+                        turn.map
+                            .tiles
+                            .get(y)
+                            .and_then(|r| r.get(x))
+                            .copied()
+                            .unwrap_or_default()
+                    });
+
+                if after != tile {
+                    mismatches.push(Mismatch::Missing {
+                        x,
+                        y,
+                        expected: tile,
+                        found: after,
+                    });
+                }
+            }
+        }
+
+        mismatches
+    }
+}
+
+/// Difference between what the answer should have placed and what the engine printed next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mismatch {
+    /// Tile changed although the answered piece doesn't cover it
+    Unexpected(TileChange),
+    /// Tile covered by the answered piece doesn't belong to the answering player
+    Missing {
+        x: usize,
+        y: usize,
+        expected: MapTile,
+        found: MapTile,
+    },
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mismatch::Unexpected(c) => write!(
+                f,
+                "({}, {}) changed from {:?} to {:?} outside the answered piece",
+                c.x, c.y, c.before, c.after
+            ),
+            Mismatch::Missing {
+                x,
+                y,
+                expected,
+                found,
+            } => write!(
+                f,
+                "({x}, {y}) is covered by the answered piece but is {found:?} instead of {expected:?}"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse::{answer::Answer, piece::Piece};
+
+    use super::*;
+
+    /// Player 1 places a domino to the right of its only tile
+    fn turn() -> Turn {
+        Turn {
+            map: "@...\n...$".parse().unwrap(),
+            piece: Piece {
+                start: 0,
+                width: 2,
+                heigth: 1,
+                tiles: vec![vec![PieceTile::Piece, PieceTile::Piece]],
+            },
+            answer: Answer {
+                player: "@".to_string(),
+                answer: Some((0, 0)),
+            },
+        }
+    }
+
+    #[test]
+    fn clean_placement() {
+        let turn = turn();
+        let after: Map = "@@..\n...$".parse().unwrap();
+        let diff = MapDiff::between(&turn.map, &after);
+
+        assert_eq!(
+            diff.changes,
+            vec![TileChange {
+                x: 1,
+                y: 0,
+                before: MapTile::Empty,
+                after: MapTile::Player1,
+            }]
+        );
+        assert_eq!(MapDiff::cause(&diff.changes[0], &turn), Some((1, 0)));
+        assert!(diff.check(&turn).is_empty());
+    }
+
+    #[test]
+    fn extra_tile() {
+        let turn = turn();
+        let after: Map = "@@..\n..$$".parse().unwrap();
+        let diff = MapDiff::between(&turn.map, &after);
+
+        let extra = TileChange {
+            x: 2,
+            y: 1,
+            before: MapTile::Empty,
+            after: MapTile::Player2,
+        };
+        assert_eq!(MapDiff::cause(&extra, &turn), None);
+        assert_eq!(diff.check(&turn), vec![Mismatch::Unexpected(extra)]);
+    }
+
+    #[test]
+    fn missing_tile() {
+        let turn = turn();
+        let diff = MapDiff::between(&turn.map, &turn.map);
+
+        assert!(diff.is_empty());
+        assert_eq!(
+            diff.check(&turn),
+            vec![Mismatch::Missing {
+                x: 1,
+                y: 0,
+                expected: MapTile::Player1,
+                found: MapTile::Empty,
+            }]
+        );
+    }
+}
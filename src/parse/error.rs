@@ -0,0 +1,52 @@
+use std::fmt::Display;
+
+/// A line of engine output the parser couldn't make sense of, the parser skips ahead
+/// to the next board after one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line of the engine output, counting from 0
+    pub line: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// Expected `exec pN : [path]`
+    Player,
+    /// Expected `Anfield W H:`
+    FieldHeader,
+    /// A map row that isn't `NNN row` as wide as the header said
+    MapRow { width: usize },
+    /// Expected `Piece W H:`
+    PieceHeader,
+    /// A piece row that isn't as wide as the header said
+    PieceRow { width: usize },
+    /// A character that isn't a tile
+    Tile(char),
+    /// A number too big to fit
+    Number(String),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line {}: ", self.line)?;
+
+        match &self.kind {
+            ParseErrorKind::Player => write!(f, "expected a player like `exec p1 : [path]`"),
+            ParseErrorKind::FieldHeader => {
+                write!(f, "expected a board header like `Anfield 20 15:`")
+            }
+            ParseErrorKind::MapRow { width } => {
+                write!(f, "expected a board row {width} tiles wide")
+            }
+            ParseErrorKind::PieceHeader => write!(f, "expected a piece header like `Piece 2 3:`"),
+            ParseErrorKind::PieceRow { width } => {
+                write!(f, "expected a piece row {width} tiles wide")
+            }
+            ParseErrorKind::Tile(c) => write!(f, "{c:?} isn't a tile"),
+            ParseErrorKind::Number(n) => write!(f, "{n} is too big"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
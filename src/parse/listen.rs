@@ -0,0 +1,122 @@
+use std::{
+    fmt::Display,
+    fs, io,
+    net::TcpListener,
+    path::PathBuf,
+    str::FromStr,
+    sync::{
+        mpsc::{self, Receiver},
+        Mutex,
+    },
+    thread,
+};
+
+use bevy::prelude::Resource;
+
+#[cfg(unix)]
+use std::os::unix::{fs::FileTypeExt, net::UnixListener};
+
+use super::Lines;
+
+/// Socket the engine output is read from instead of stdin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Listen {
+    /// `tcp://0.0.0.0:7878`
+    Tcp(String),
+    /// `unix:/tmp/filler.sock`
+    Unix(PathBuf),
+}
+
+impl FromStr for Listen {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(address) = s.strip_prefix("tcp://") {
+            Ok(Listen::Tcp(address.to_string()))
+        } else if let Some(path) = s.strip_prefix("unix:") {
+            Ok(Listen::Unix(PathBuf::from(path.trim_start_matches("//"))))
+        } else {
+            Err("expected tcp://HOST:PORT or unix:/PATH".to_string())
+        }
+    }
+}
+
+impl Display for Listen {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Listen::Tcp(address) => write!(f, "tcp://{address}"),
+            Listen::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// Games coming in over the sockets, every connection is a game of its own
+#[derive(Resource)]
+pub struct Listener(Mutex<Receiver<(String, Lines)>>);
+
+impl Listener {
+    /// Accepts connections on every address, each one is read like the game piped to stdin
+    pub fn bind(addresses: &[Listen]) -> io::Result<Self> {
+        let (games, receiver) = mpsc::channel();
+
+        for address in addresses {
+            let games = games.clone();
+
+            match address {
+                Listen::Tcp(address) => {
+                    let listener = TcpListener::bind(address)?;
+                    thread::spawn(move || {
+                        for stream in listener.incoming().flatten() {
+                            let name = stream
+                                .peer_addr()
+                                .map_or_else(|_| "tcp".to_string(), |peer| format!("tcp://{peer}"));
+
+                            if games.send((name, Lines::read(stream))).is_err() {
+                                break;
+                            }
+                        }
+                    });
+                }
+                #[cfg(unix)]
+                Listen::Unix(path) => {
+                    // a socket left behind by an earlier run would make binding fail, anything
+                    // else at that path isn't ours to remove
+                    if let Ok(metadata) = fs::symlink_metadata(path) {
+                        if !metadata.file_type().is_socket() {
+                            return Err(io::Error::new(
+                                io::ErrorKind::AlreadyExists,
+                                format!("{} exists and isn't a socket", path.display()),
+                            ));
+                        }
+
+                        fs::remove_file(path)?;
+                    }
+
+                    let listener = UnixListener::bind(path)?;
+                    let name = format!("unix:{}", path.display());
+                    thread::spawn(move || {
+                        for stream in listener.incoming().flatten() {
+                            if games.send((name.clone(), Lines::read(stream))).is_err() {
+                                break;
+                            }
+                        }
+                    });
+                }
+                #[cfg(not(unix))]
+                Listen::Unix(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::Unsupported,
+                        "unix sockets only work on unix",
+                    ))
+                }
+            }
+        }
+
+        Ok(Listener(Mutex::new(receiver)))
+    }
+
+    /// Connections accepted since the last call, with a name to tell them apart
+    pub fn accept(&self) -> Vec<(String, Lines)> {
+        self.0.lock().unwrap().try_iter().collect()
+    }
+}
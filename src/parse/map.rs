@@ -1,12 +1,22 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
 use bevy::prelude::Component;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Default)]
+use super::{
+    piece::{Piece, PieceTile},
+    tile::{deserialize_rows, serialize_rows, Tile},
+};
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Map {
     pub start: usize,
     pub width: usize,
     pub heigth: usize,
+    #[serde(
+        serialize_with = "serialize_rows",
+        deserialize_with = "deserialize_rows"
+    )]
     pub tiles: Vec<Vec<MapTile>>,
 }
 
@@ -16,15 +26,7 @@ impl Display for Map {
 
         for row in &self.tiles {
             for tile in row {
-                write!(
-                    f,
-                    "{}",
-                    match tile {
-                        MapTile::Player1 => '@',
-                        MapTile::Player2 => '$',
-                        _ => '.',
-                    }
-                )?;
+                write!(f, "{}", tile.to_char())?;
             }
 
             writeln!(f)?;
@@ -34,6 +36,107 @@ impl Display for Map {
     }
 }
 
+impl Map {
+    /// Copy of the map with the piece placed at the answered position
+    pub fn with_piece(&self, piece: &Piece, (x, y): (usize, usize), tile: MapTile) -> Map {
+        let mut map = self.clone();
+
+        for (py, row) in piece.tiles.iter().enumerate() {
+            for (px, piece_tile) in row.iter().enumerate() {
+                if *piece_tile != PieceTile::Piece {
+                    continue;
+                }
+
+                // answers come from the players, they can be anywhere
+                let (Some(x), Some(y)) = (x.checked_add(px), y.checked_add(py)) else {
+                    continue;
+                };
+
+                if let Some(t) = map.tiles.get_mut(y).and_then(|r| r.get_mut(x)) {
+                    *t = tile;
+                }
+            }
+        }
+
+        map
+    }
+
+    /// Whether the engine accepts the piece at (x, y), it has to cover exactly one tile of
+    /// the player and none of the opponent without falling off the board
+    pub fn fits(&self, piece: &Piece, (x, y): (usize, usize), tile: MapTile) -> bool {
+        let mut own = 0;
+
+        for (py, row) in piece.tiles.iter().enumerate() {
+            for (px, piece_tile) in row.iter().enumerate() {
+                if *piece_tile != PieceTile::Piece {
+                    continue;
+                }
+
+                let covered = x
+                    .checked_add(px)
+                    .zip(y.checked_add(py))
+                    .and_then(|(x, y)| self.tiles.get(y)?.get(x));
+                match covered {
+                    Some(t) if *t == tile => own += 1,
+                    Some(MapTile::Empty) => {}
+                    _ => return false,
+                }
+            }
+        }
+
+        own == 1
+    }
+
+    pub fn count(&self, tile: MapTile) -> usize {
+        self.tiles.iter().flatten().filter(|t| **t == tile).count()
+    }
+
+    /// Formats the map the way map files given to the game engine are written
+    pub fn to_map_file(&self) -> String {
+        let mut text = String::new();
+
+        for row in &self.tiles {
+            for tile in row {
+                text.push(tile.to_char());
+            }
+
+            text.push('\n');
+        }
+
+        text
+    }
+}
+
+/// Parses map files, also accepts rows prefixed with the row number like the engine prints them
+impl FromStr for Map {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tiles: Vec<Vec<MapTile>> = Vec::new();
+
+        for (i, line) in s.lines().filter(|l| !l.trim().is_empty()).enumerate() {
+            let row = line.split_ascii_whitespace().last().unwrap();
+            let row = row
+                .chars()
+                .map(|c| MapTile::try_from_char(c).ok_or(format!("invalid tile {c:?} on row {i}")))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if tiles.first().is_some_and(|first| first.len() != row.len()) {
+                return Err(format!("row {i} has a different width"));
+            }
+
+            tiles.push(row);
+        }
+
+        Ok(Map {
+            start: 0,
+            width: tiles.first().map_or(0, Vec::len),
+            heigth: tiles.len(),
+            tiles,
+        })
+    }
+}
+
 #[derive(Default, Component, Copy, Clone, Debug, PartialEq, Eq)]
 pub enum MapTile {
     Player1, // a & @
@@ -46,11 +149,25 @@ pub enum MapTile {
 
 impl MapTile {
     pub fn from_char(c: char) -> Self {
+        Self::try_from_char(c).unwrap()
+    }
+}
+
+impl Tile for MapTile {
+    fn try_from_char(c: char) -> Option<Self> {
         match c {
-            'a' | '@' => Self::Player1,
-            's' | '$' => Self::Player2,
-            '.' => Self::Empty,
-            _ => unreachable!(),
+            'a' | '@' => Some(Self::Player1),
+            's' | '$' => Some(Self::Player2),
+            '.' => Some(Self::Empty),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Self::Player1 => '@',
+            Self::Player2 => '$',
+            _ => '.',
         }
     }
 }
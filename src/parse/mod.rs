@@ -1,37 +1,69 @@
-use std::io;
-
-use bevy::{
-    prelude::{AssetServer, Color, Commands, Component, Res, ResMut, Resource, TextBundle},
-    text::{TextAlignment, TextStyle},
-    ui::{PositionType, Style, UiRect, Val},
+use std::{
+    fmt::Display,
+    io::{self, BufRead, BufReader, Read},
+    sync::{
+        mpsc::{self, Receiver, Sender, TryRecvError},
+        Mutex,
+    },
+    thread,
 };
+
+use bevy::prelude::{Component, Query, Res};
 use regex::Regex;
 
 use crate::Args;
 
 use self::{
     answer::Answer,
+    claims::Claims,
+    diff::MapDiff,
+    error::{ParseError, ParseErrorKind},
     map::{Map, MapTile},
     piece::{Piece, PieceTile},
     player::Player,
-    win::Win,
+    tile::Tile,
+    turn::Turn,
+    win::{GameOutcome, Win},
 };
 
 pub mod answer;
+pub mod claims;
+pub mod diff;
+pub mod error;
+pub mod listen;
 pub mod map;
 pub mod piece;
 pub mod player;
+pub mod replay;
+pub mod tile;
+pub mod turn;
 pub mod win;
 
+#[cfg(test)]
+mod tests;
+
+#[cfg(test)]
+mod proptests;
+
 lazy_static! {
     pub static ref PLAYER_REGEX: Regex = Regex::new(r"exec p([0-9]) : \[(.+)]").unwrap();
     pub static ref FIELD_SIZES_REGEX: Regex = Regex::new("Anfield ([0-9]+) ([0-9]+):").unwrap();
     pub static ref PIECE_SIZE_REGEX: Regex = Regex::new("Piece ([0-9]+) ([0-9]+):").unwrap();
     pub static ref ANSWER_REGEX: Regex =
         Regex::new(r"-> Answer \((.)\): ([0-9]+) ([0-9]+)").unwrap();
-    pub static ref SEED_REGEX: Regex = Regex::new(r"seed: ([0-9]+)").unwrap();
-    pub static ref SCORE_REGEX: Regex = Regex::new(r"Player([0-9]) \((.+)\): ([0-9]+)").unwrap();
-    pub static ref WINNER_REGEX: Regex = Regex::new(r"Player([0-9]) won!").unwrap();
+    // the seed and score lines start the end block, whole lines so players can't start it
+    pub static ref SEED_REGEX: Regex = Regex::new(r"(?i)^seed:\s*([0-9]+)$").unwrap();
+    pub static ref SCORE_REGEX: Regex =
+        Regex::new(r"(?i)^player ?([12])(?: \((.*)\))?: ([0-9]+)$").unwrap();
+    pub static ref WINNER_REGEX: Regex = Regex::new(r"(?i)player ?([12]) (won|wins)").unwrap();
+    pub static ref DRAW_REGEX: Regex =
+        Regex::new(r"(?i)\b(draw|tie)\b").unwrap();
+    pub static ref TIMEOUT_REGEX: Regex =
+        Regex::new(r"(?i)player ?([12]).*\b(timed out|timeout|time out)").unwrap();
+    pub static ref CRASH_REGEX: Regex =
+        Regex::new(r"(?i)player ?([12]).*\b(crash|panic|exited|died|killed)").unwrap();
+    pub static ref DISQUALIFIED_REGEX: Regex =
+        Regex::new(r"(?i)player ?([12]).*\bdisqualified").unwrap();
 }
 
 #[derive(Debug, Default, Clone)]
@@ -58,9 +90,9 @@ impl Mode {
     }
 }
 
-#[derive(Resource, Debug, Default, Clone, Component)]
+/// One game, every game shown is an entity with its own state
+#[derive(Debug, Default, Clone, Component)]
 pub struct State {
-    pub finished_parsing: bool,
     pub iteration: usize,
 
     pub player1: Player,
@@ -76,219 +108,469 @@ pub struct State {
 
     pub answer: Answer,
     pub win: Win,
+
+    /// Every turn parsed so far, in order
+    pub turns: Vec<Turn>,
+    pub claims: Claims,
+
+    /// Board after the last answer, known once the game has finished
+    pub last_map: Option<Map>,
+
+    /// Why the engine output stopped coming, if it stopped before the game finished
+    pub stream_end: Option<StreamEnd>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamEnd {
+    /// The engine exited or the pipe was closed
+    Closed,
+    /// Reading failed, the message of the io error
+    Failed(String),
 }
 
-fn execute_antfield_header(i: usize, line: String, state: &mut State) {
-    let caps = FIELD_SIZES_REGEX.captures(&line).unwrap();
+impl Display for StreamEnd {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamEnd::Closed => write!(f, "the engine closed its output"),
+            StreamEnd::Failed(err) => write!(f, "reading the engine output failed: {err}"),
+        }
+    }
+}
 
-    state.map = state.unfinished_map.clone();
-    state.unfinished_map.start = i;
-    state.unfinished_map.width = caps.get(1).unwrap().as_str().parse::<usize>().unwrap();
-    state.unfinished_map.heigth = caps.get(2).unwrap().as_str().parse::<usize>().unwrap();
-    state.unfinished_map.tiles = Vec::new();
+/// What a line of engine output completed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// One of the two players, by number
+    Player(usize),
+    /// A turn, by its index in `State::turns`
+    Turn(usize),
+    /// The end of game block, the outcome is in `State::win`
+    Finished,
+}
 
-    state.mode.next()
+/// What the reading threads pass on to the parser
+#[derive(Debug)]
+pub enum Message {
+    Line(String),
+    /// The output stopped, nothing follows
+    Ended(StreamEnd),
 }
 
-pub fn parse(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut state: ResMut<State>,
-    args: Res<Args>,
-) {
-    if state.win.finished {
-        return;
+/// Lines of the engine output, read on their own thread so waiting for the engine never
+/// holds up a frame. Sits next to the state of the game it feeds
+#[derive(Component)]
+pub struct Lines(Mutex<Receiver<Message>>);
+
+impl Lines {
+    pub fn stdin() -> Self {
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || read_stream(io::stdin().lock(), &sender));
+
+        Lines(Mutex::new(receiver))
     }
 
-    let mut line = String::new();
-    io::stdin().read_line(&mut line).unwrap();
+    /// Reads any stream, like a socket connection, the same way as stdin
+    pub fn read(stream: impl Read + Send + 'static) -> Self {
+        let (sender, receiver) = mpsc::channel();
 
-    if state.iteration == 0 || state.iteration == 1 {
-        // player parsing
-        let caps = PLAYER_REGEX.captures(&line).unwrap();
+        thread::spawn(move || read_stream(BufReader::new(stream), &sender));
 
-        let player = Player {
-            num: caps.get(1).unwrap().as_str().parse::<usize>().unwrap(),
-            path: caps.get(2).unwrap().as_str().to_string(),
+        Lines(Mutex::new(receiver))
+    }
+}
+
+/// Sends every line and then why they stopped, false once nobody is listening anymore
+fn read_stream(reader: impl BufRead, sender: &Sender<Message>) -> bool {
+    for line in reader.lines() {
+        let message = match line {
+            Ok(line) => Message::Line(line),
+            Err(err) => {
+                return sender
+                    .send(Message::Ended(StreamEnd::Failed(err.to_string())))
+                    .is_ok()
+            }
         };
 
-        if args.debug {
-            println!("{player}");
+        if sender.send(message).is_err() {
+            return false;
+        }
+    }
+
+    sender.send(Message::Ended(StreamEnd::Closed)).is_ok()
+}
+
+/// A player that lost by breaking the rules or the engine, rather than on points
+fn failure(line: &str) -> Option<GameOutcome> {
+    let player = |regex: &Regex| {
+        regex
+            .captures(line)
+            .map(|caps| caps.get(1).unwrap().as_str().parse::<usize>().unwrap())
+    };
+
+    player(&DISQUALIFIED_REGEX)
+        .map(|player| GameOutcome::Disqualified { player })
+        .or_else(|| player(&TIMEOUT_REGEX).map(|player| GameOutcome::Timeout { player }))
+        .or_else(|| player(&CRASH_REGEX).map(|player| GameOutcome::Crash { player }))
+}
+
+impl State {
+    /// Parses a whole game at once, used when there is nothing to show live
+    pub fn from_reader(reader: impl BufRead, debug: bool) -> io::Result<Self> {
+        let mut state = State::default();
+
+        for line in reader.lines() {
+            if let Err(err) = state.parse_line(&line?, debug) {
+                eprintln!("{err}");
+            }
+
+            if state.win.finished {
+                break;
+            }
+        }
+
+        state.end_of_stream(debug);
+        Ok(state)
+    }
+
+    /// Number of boards there are to show, one for every turn and the board after the last answer
+    pub fn board_count(&self) -> usize {
+        self.turns.len() + usize::from(self.last_map.is_some())
+    }
+
+    /// Board before the answer of the given turn, or the final board right after the last turn
+    pub fn board(&self, i: usize) -> Option<&Map> {
+        match self.turns.get(i) {
+            Some(turn) => Some(&turn.map),
+            None if i == self.turns.len() => self.last_map.as_ref(),
+            None => None,
+        }
+    }
+
+    /// Board after the last answer, which the engine never prints on its own
+    pub fn final_map(&self) -> Option<Map> {
+        let last = self.turns.last()?;
+
+        // the engine leaves the board as it was when it rejects the answer
+        Some(match (last.answer.answer, last.answer.tile()) {
+            (Some(pos), Some(tile)) if last.map.fits(&last.piece, pos, tile) => {
+                last.map.with_piece(&last.piece, pos, tile)
+            }
+            _ => last.map.clone(),
+        })
+    }
+
+    /// Feeds one line of the engine output to the parser, a line that doesn't fit
+    /// drops the board it belongs to and the parser waits for the next one
+    pub fn parse_line(&mut self, line: &str, debug: bool) -> Result<Option<Event>, ParseError> {
+        if self.win.finished {
+            return Ok(None);
+        }
+
+        let result = self.parse_mode(line, debug);
+        if result.is_err() {
+            self.mode = Mode::WaitPeriod;
         }
 
-        if state.iteration == 0 {
-            state.player1 = player
-        } else {
-            state.player2 = player
+        self.iteration += 1;
+        result
+    }
+
+    fn parse_mode(&mut self, line: &str, debug: bool) -> Result<Option<Event>, ParseError> {
+        let error = |kind| ParseError {
+            line: self.iteration,
+            kind,
+        };
+
+        if self.iteration == 0 || self.iteration == 1 {
+            // player parsing
+            let caps = PLAYER_REGEX
+                .captures(line)
+                .ok_or_else(|| error(ParseErrorKind::Player))?;
+
+            let player = Player {
+                num: number(caps.get(1).unwrap().as_str(), self.iteration)?,
+                path: caps.get(2).unwrap().as_str().to_string(),
+            };
+
+            if debug {
+                println!("{player}");
+            }
+
+            let num = player.num;
+            if self.iteration == 0 {
+                self.player1 = player
+            } else {
+                self.player2 = player
+            }
+
+            return Ok(Some(Event::Player(num)));
         }
-    } else {
-        match state.mode {
-            Mode::AntFieldHeader => execute_antfield_header(state.iteration, line, &mut state),
+
+        match self.mode {
             Mode::AntField => {
-                if state.iteration - state.unfinished_map.start == 1 {
-                } else {
-                    // skips first map line which is useless
-                    state.unfinished_map.tiles.push(
-                        line.split_ascii_whitespace()
-                            .nth(1)
-                            .unwrap()
-                            .chars()
-                            .map(MapTile::from_char)
-                            .collect(),
-                    );
-
-                    if state.iteration - state.unfinished_map.start - 1
-                        == state.unfinished_map.heigth
-                    {
-                        if args.debug {
-                            println!("{}", state.unfinished_map);
-                        }
-                        state.mode.next();
+                // skips first map line which only numbers the columns
+                if self.iteration - self.unfinished_map.start > 1 {
+                    let width = self.unfinished_map.width;
+                    let row = line
+                        .split_ascii_whitespace()
+                        .nth(1)
+                        .ok_or_else(|| error(ParseErrorKind::MapRow { width }))?;
+
+                    let tiles = row
+                        .chars()
+                        .map(|c| {
+                            MapTile::try_from_char(c).ok_or_else(|| error(ParseErrorKind::Tile(c)))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if tiles.len() != width {
+                        return Err(error(ParseErrorKind::MapRow { width }));
+                    }
+
+                    self.unfinished_map.tiles.push(tiles);
+                }
+
+                if self.unfinished_map.tiles.len() == self.unfinished_map.heigth {
+                    if debug {
+                        println!("{}", self.unfinished_map);
                     }
+                    self.mode.next();
                 }
             }
             Mode::PieceHeader => {
-                let caps = PIECE_SIZE_REGEX.captures(&line).unwrap();
-
-                state.piece = state.unfinished_piece.clone();
-                state.unfinished_piece.start = state.iteration;
-                state.unfinished_piece.width =
-                    caps.get(1).unwrap().as_str().parse::<usize>().unwrap();
-                state.unfinished_piece.heigth =
-                    caps.get(2).unwrap().as_str().parse::<usize>().unwrap();
-                state.unfinished_piece.tiles = Vec::new();
+                let caps = PIECE_SIZE_REGEX
+                    .captures(line)
+                    .ok_or_else(|| error(ParseErrorKind::PieceHeader))?;
+
+                // the piece is built up in unfinished_piece and only replaces piece
+                // once all of its rows are in
+                self.unfinished_piece = Piece {
+                    start: self.iteration,
+                    width: number(caps.get(1).unwrap().as_str(), self.iteration)?,
+                    heigth: number(caps.get(2).unwrap().as_str(), self.iteration)?,
+                    tiles: Vec::new(),
+                };
 
-                state.mode.next();
+                self.mode.next();
+                if self.unfinished_piece.heigth == 0 {
+                    self.finish_piece(debug);
+                }
             }
             Mode::Piece => {
-                state
-                    .piece
-                    .tiles
-                    .push(line.trim().chars().map(PieceTile::from_char).collect());
-
-                if state.iteration - state.unfinished_piece.start == state.unfinished_piece.heigth {
-                    if args.debug {
-                        println!("{}", state.unfinished_piece);
-                    }
-                    state.mode.next();
+                let width = self.unfinished_piece.width;
+                let tiles = line
+                    .trim()
+                    .chars()
+                    .map(|c| {
+                        PieceTile::try_from_char(c).ok_or_else(|| error(ParseErrorKind::Tile(c)))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if tiles.len() != width {
+                    return Err(error(ParseErrorKind::PieceRow { width }));
+                }
+
+                self.unfinished_piece.tiles.push(tiles);
+
+                if self.unfinished_piece.tiles.len() == self.unfinished_piece.heigth {
+                    self.finish_piece(debug);
                 }
             }
             Mode::Answer => {
-                state.answer = match ANSWER_REGEX.captures(&line) {
+                self.answer = match ANSWER_REGEX.captures(line) {
                     Some(caps) => Answer {
                         player: caps.get(1).unwrap().as_str().to_string(),
-                        answer: Some((
-                            caps.get(2).unwrap().as_str().parse::<usize>().unwrap(),
-                            caps.get(3).unwrap().as_str().parse::<usize>().unwrap(),
-                        )),
+                        // coordinates too big for any board are as good as no answer
+                        answer: caps
+                            .get(2)
+                            .unwrap()
+                            .as_str()
+                            .parse::<usize>()
+                            .ok()
+                            .zip(caps.get(3).unwrap().as_str().parse::<usize>().ok()),
                     },
-                    None => Answer::default(),
+                    None => {
+                        // a player that fails to answer is reported where its answer would be
+                        if let Some(outcome) = failure(line) {
+                            self.win.outcome = Some(outcome);
+                        }
+
+                        Answer::default()
+                    }
                 };
 
-                if args.debug {
-                    println!("{}", state.answer);
+                if debug {
+                    println!("{}", self.answer);
                 }
-                state.mode.next();
+
+                let diff = self.turns.last().map(|previous| {
+                    // the new board should be the previous one with just the answered piece added
+                    let diff = MapDiff::between(&previous.map, &self.unfinished_map);
+                    if debug {
+                        for mismatch in diff.check(previous) {
+                            eprintln!("Turn {}: {mismatch}", self.turns.len() - 1);
+                        }
+                    }
+
+                    diff
+                });
+                self.claims
+                    .record(self.turns.len(), &self.unfinished_map, diff.as_ref());
+
+                self.turns.push(Turn {
+                    map: self.unfinished_map.clone(),
+                    piece: self.piece.clone(),
+                    answer: self.answer.clone(),
+                });
+                self.mode.next();
+
+                return Ok(Some(Event::Turn(self.turns.len() - 1)));
             }
-            Mode::WaitPeriod => {
-                if FIELD_SIZES_REGEX.is_match(&line) {
+            // a game can end before its first board, when a player fails right away
+            Mode::AntFieldHeader | Mode::WaitPeriod => {
+                if FIELD_SIZES_REGEX.is_match(line) {
                     // repeats loop
-                    state.finished_parsing = true;
-                    state.mode.next();
-                    execute_antfield_header(state.iteration, line, &mut state)
+                    self.mode = Mode::AntFieldHeader;
+                    self.parse_antfield_header(line)?;
                 } else {
-                    if state.win.start.is_none() && SEED_REGEX.is_match(&line) {
-                        state.win.start = Some(state.iteration);
-                    }
+                    self.parse_end_line(line, debug)?;
 
-                    if let Some(i) = state.win.start {
-                        match state.iteration - i {
-                            0 => {
-                                state.win.seed = SEED_REGEX
-                                    .captures(&line)
-                                    .unwrap()
-                                    .get(1)
-                                    .unwrap()
-                                    .as_str()
-                                    .to_string()
-                            }
-                            1 | 2 => {
-                                let caps = SCORE_REGEX.captures(&line).unwrap();
-                                let score = caps.get(3).unwrap().as_str().parse::<usize>().unwrap();
-
-                                match caps.get(1).unwrap().as_str() {
-                                    "1" => state.win.player1_score = score,
-                                    _ => state.win.player2_score = score,
-                                }
-                            }
-                            _ => {
-                                state.win.finished = true;
-                                state.win.winner = WINNER_REGEX
-                                    .captures(&line)
-                                    .unwrap()
-                                    .get(1)
-                                    .unwrap()
-                                    .as_str()
-                                    .parse::<usize>()
-                                    .unwrap();
-
-                                if args.debug {
-                                    println!("{}", state.win);
-                                }
-
-                                let font = asset_server.load("fonts/Roboto-Regular.ttf");
-
-                                commands.spawn(
-                                    TextBundle::from_section(
-                                        format!("Player {} won!", state.win.winner),
-                                        TextStyle {
-                                            font: font.clone(),
-                                            font_size: 60.0,
-                                            color: Color::WHITE,
-                                        },
-                                    )
-                                    .with_text_alignment(TextAlignment::CENTER_LEFT)
-                                    .with_style(Style {
-                                        position_type: PositionType::Absolute,
-                                        position: UiRect {
-                                            bottom: Val::Percent(50.0),
-                                            right: Val::Percent(40.0),
-                                            ..Default::default()
-                                        },
-                                        ..Default::default()
-                                    }),
-                                );
-
-                                commands.spawn(
-                                    TextBundle::from_section(
-                                        format!(
-                                            "Player 1 score: {}\nPlayer 2 score: {}",
-                                            state.win.player1_score, state.win.player2_score
-                                        ),
-                                        TextStyle {
-                                            font,
-                                            font_size: 30.0,
-                                            color: Color::GRAY,
-                                        },
-                                    )
-                                    .with_text_alignment(TextAlignment::CENTER_LEFT)
-                                    .with_style(Style {
-                                        position_type: PositionType::Absolute,
-                                        position: UiRect {
-                                            bottom: Val::Percent(40.0),
-                                            right: Val::Percent(43.0),
-                                            ..Default::default()
-                                        },
-                                        ..Default::default()
-                                    }),
-                                );
-                            }
-                        }
+                    if self.win.finished {
+                        return Ok(Some(Event::Finished));
                     }
                 }
             }
         };
-    };
 
-    state.iteration += 1;
+        Ok(None)
+    }
+
+    fn parse_antfield_header(&mut self, line: &str) -> Result<(), ParseError> {
+        let caps = FIELD_SIZES_REGEX.captures(line).ok_or(ParseError {
+            line: self.iteration,
+            kind: ParseErrorKind::FieldHeader,
+        })?;
+
+        self.map = self.unfinished_map.clone();
+        self.unfinished_map.start = self.iteration;
+        self.unfinished_map.width = number(caps.get(1).unwrap().as_str(), self.iteration)?;
+        self.unfinished_map.heigth = number(caps.get(2).unwrap().as_str(), self.iteration)?;
+        self.unfinished_map.tiles = Vec::new();
+
+        self.mode.next();
+        Ok(())
+    }
+}
+
+/// Numbers are only ever matched as digits, all that can go wrong is their size
+fn number(text: &str, line: usize) -> Result<usize, ParseError> {
+    text.parse::<usize>().map_err(|_| ParseError {
+        line,
+        kind: ParseErrorKind::Number(text.to_string()),
+    })
+}
+
+impl State {
+    /// Lines between the turns, the end block is a seed, the scores and the result in
+    /// whatever order and wording the engine uses. Players print between the turns too,
+    /// so the result is only looked for once the seed or a score started the end block
+    fn parse_end_line(&mut self, line: &str, debug: bool) -> Result<(), ParseError> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(caps) = SEED_REGEX.captures(line) {
+            self.win.start.get_or_insert(self.iteration);
+            self.win.seed = caps.get(1).unwrap().as_str().to_string();
+        } else if let Some(caps) = SCORE_REGEX.captures(line) {
+            self.win.start.get_or_insert(self.iteration);
+            let score = number(caps.get(3).unwrap().as_str(), self.iteration)?;
+
+            match caps.get(1).unwrap().as_str() {
+                "1" => self.win.player1_score = score,
+                _ => self.win.player2_score = score,
+            }
+        } else if self.win.start.is_none() {
+            return Ok(());
+        } else if let Some(outcome) = failure(line) {
+            self.win.outcome = Some(outcome);
+        } else if let Some(caps) = WINNER_REGEX.captures(line) {
+            let player = caps.get(1).unwrap().as_str().parse::<usize>().unwrap();
+
+            // the winner line says less than a player failing before it
+            self.win.outcome.get_or_insert(GameOutcome::Win { player });
+            self.finish(debug);
+        } else if DRAW_REGEX.is_match(line) {
+            self.win.outcome.get_or_insert(GameOutcome::Draw);
+            self.finish(debug);
+        }
+
+        Ok(())
+    }
+
+    /// The engine stopped printing, a game without a result by then was aborted
+    pub fn end_of_stream(&mut self, debug: bool) {
+        if self.win.finished {
+            return;
+        }
+
+        self.win.outcome.get_or_insert(GameOutcome::Aborted);
+        self.finish(debug);
+
+        // no end block means no scores either, the tiles each player holds are the next best thing
+        if self.win.start.is_none() {
+            if let Some(map) = &self.last_map {
+                self.win.player1_score = map.count(MapTile::Player1);
+                self.win.player2_score = map.count(MapTile::Player2);
+            }
+        }
+    }
+
+    fn finish_piece(&mut self, debug: bool) {
+        self.piece = self.unfinished_piece.clone();
+
+        if debug {
+            println!("{}", self.piece);
+        }
+        self.mode.next();
+    }
+
+    fn finish(&mut self, debug: bool) {
+        self.win.finished = true;
+        self.last_map = self.final_map();
+
+        if debug {
+            println!("{}", self.win);
+        }
+    }
+}
+
+pub fn parse(mut games: Query<(&mut State, &Lines)>, args: Res<Args>) {
+    for (mut state, lines) in games.iter_mut() {
+        // everything the engine printed since the last frame, the playback decides what is shown
+        let receiver = lines.0.lock().unwrap();
+        loop {
+            match receiver.try_recv() {
+                Ok(Message::Line(line)) => {
+                    if let Err(err) = state.parse_line(&line, args.debug) {
+                        eprintln!("{err}");
+                    }
+                }
+                Ok(Message::Ended(end)) => {
+                    if !state.win.finished {
+                        state.stream_end = Some(end);
+                        state.end_of_stream(args.debug);
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    if !state.win.finished {
+                        state.stream_end = Some(StreamEnd::Closed);
+                        state.end_of_stream(args.debug);
+                    }
+                    break;
+                }
+            }
+        }
+    }
 }
@@ -1,10 +1,18 @@
 use std::fmt::Display;
 
-#[derive(Debug, Default, Clone)]
+use serde::{Deserialize, Serialize};
+
+use super::tile::{deserialize_rows, serialize_rows, Tile};
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Piece {
     pub start: usize,
     pub width: usize,
     pub heigth: usize,
+    #[serde(
+        serialize_with = "serialize_rows",
+        deserialize_with = "deserialize_rows"
+    )]
     pub tiles: Vec<Vec<PieceTile>>,
 }
 
@@ -14,14 +22,7 @@ impl Display for Piece {
 
         for row in &self.tiles {
             for tile in row {
-                write!(
-                    f,
-                    "{}",
-                    match tile {
-                        PieceTile::Piece => "O",
-                        PieceTile::Empty => ".",
-                    }
-                )?;
+                write!(f, "{}", tile.to_char())?;
             }
 
             writeln!(f)?;
@@ -39,10 +40,23 @@ pub enum PieceTile {
 
 impl PieceTile {
     pub fn from_char(c: char) -> Self {
+        Self::try_from_char(c).unwrap()
+    }
+}
+
+impl Tile for PieceTile {
+    fn try_from_char(c: char) -> Option<Self> {
         match c {
-            'O' => Self::Piece,
-            '.' => Self::Empty,
-            _ => unreachable!(),
+            'O' => Some(Self::Piece),
+            '.' => Some(Self::Empty),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Self::Piece => 'O',
+            Self::Empty => '.',
         }
     }
 }
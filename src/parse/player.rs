@@ -1,6 +1,8 @@
 use std::fmt::Display;
 
-#[derive(Debug, Default, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Player {
     pub num: usize,
     pub path: String,
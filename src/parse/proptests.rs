@@ -0,0 +1,236 @@
+use std::fs;
+
+use proptest::prelude::*;
+
+use super::{Event, State};
+
+/// Feeds every line and the end of the stream, what a live game or an export does
+fn parse(lines: &[String]) -> (State, Vec<Event>) {
+    let mut state = State::default();
+    let mut events = Vec::new();
+
+    for line in lines {
+        // errors are fine, panics aren't
+        if let Ok(Some(event)) = state.parse_line(line, false) {
+            events.push(event);
+        }
+    }
+    state.end_of_stream(false);
+
+    (state, events)
+}
+
+/// Whatever went in, every turn that came out is whole
+fn check_turns(state: &State) {
+    for turn in &state.turns {
+        assert_eq!(turn.map.tiles.len(), turn.map.heigth);
+        assert!(turn.map.tiles.iter().all(|row| row.len() == turn.map.width));
+        assert_eq!(turn.piece.tiles.len(), turn.piece.heigth);
+        assert!(turn
+            .piece
+            .tiles
+            .iter()
+            .all(|row| row.len() == turn.piece.width));
+    }
+
+    assert!(state.win.finished && state.win.outcome.is_some());
+    let _ = state.final_map();
+}
+
+fn fixture_lines() -> Vec<Vec<String>> {
+    [
+        "win",
+        "draw",
+        "invalid_answer",
+        "timeout",
+        "tiny_map",
+        "aborted",
+    ]
+    .iter()
+    .map(|name| {
+        let path = format!("{}/tests/fixtures/{name}.txt", env!("CARGO_MANIFEST_DIR"));
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    })
+    .collect()
+}
+
+#[derive(Debug, Clone)]
+enum Mutation {
+    Delete,
+    Duplicate,
+    Replace(String),
+    Insert(String),
+    Truncate(usize),
+}
+
+fn mutation() -> impl Strategy<Value = Mutation> {
+    // lines that look like the engine output make it deeper into the state machine
+    let line = prop_oneof![
+        ".*",
+        "Anfield [0-9]{1,3} [0-9]{1,3}:",
+        "Piece [0-9]{1,2} [0-9]{1,2}:",
+        "[0-9]{3} [.@$as]{0,30}",
+        "[.O]{0,6}",
+        r"-> Answer \([@$]\): [0-9]{1,25} [0-9]{1,25}",
+        "Player[12] (won!|timed out|crashed|was disqualified)",
+        "Player[12] \\(x\\): [0-9]{1,25}",
+    ];
+
+    prop_oneof![
+        Just(Mutation::Delete),
+        Just(Mutation::Duplicate),
+        line.clone().prop_map(Mutation::Replace),
+        line.prop_map(Mutation::Insert),
+        any::<usize>().prop_map(Mutation::Truncate),
+    ]
+}
+
+fn apply(lines: &mut Vec<String>, at: usize, mutation: &Mutation) {
+    if lines.is_empty() {
+        return;
+    }
+    let at = at % lines.len();
+
+    match mutation {
+        Mutation::Delete => {
+            lines.remove(at);
+        }
+        Mutation::Duplicate => lines.insert(at, lines[at].clone()),
+        Mutation::Replace(line) => lines[at] = line.clone(),
+        Mutation::Insert(line) => lines.insert(at, line.clone()),
+        Mutation::Truncate(i) => {
+            let line = &mut lines[at];
+            let mut i = i % (line.len() + 1);
+            while !line.is_char_boundary(i) {
+                i -= 1;
+            }
+            line.truncate(i);
+        }
+    }
+}
+
+/// A game the way the engine prints it, every board the same so it stays small
+fn transcript() -> impl Strategy<Value = (Vec<String>, usize)> {
+    (1..12usize, 1..12usize, 1..5usize, 1..5usize, 0..8usize).prop_flat_map(
+        |(width, heigth, piece_width, piece_heigth, turns)| {
+            (
+                proptest::collection::vec("[.@$]", width * heigth),
+                proptest::collection::vec("[.O]", piece_width * piece_heigth),
+            )
+                .prop_map(move |(tiles, piece)| {
+                    let mut lines = vec![
+                        "exec p1 : [robots/bender]".to_string(),
+                        "exec p2 : [robots/terminator]".to_string(),
+                    ];
+
+                    for turn in 0..turns {
+                        lines.push(format!("Anfield {width} {heigth}:"));
+                        lines.push(format!("    {}", &"0123456789".repeat(2)[..width]));
+                        for (y, row) in tiles.chunks(width).enumerate() {
+                            lines.push(format!("{y:03} {}", row.concat()));
+                        }
+
+                        lines.push(format!("Piece {piece_width} {piece_heigth}:"));
+                        for row in piece.chunks(piece_width) {
+                            lines.push(row.concat());
+                        }
+
+                        let player = if turn % 2 == 0 { '@' } else { '$' };
+                        lines.push(format!(
+                            "-> Answer ({player}): {} {}",
+                            turn % width,
+                            turn % heigth
+                        ));
+                    }
+
+                    lines.push("seed: 42".to_string());
+                    lines.push("Player1 (robots/bender): 1".to_string());
+                    lines.push("Player2 (robots/terminator): 1".to_string());
+                    lines.push("Player1 won!".to_string());
+
+                    (lines, turns)
+                })
+        },
+    )
+}
+
+/// Two turns both answered at (x, y), wherever that is, so the answer is checked against
+/// the next board and placed on the final one
+fn answered_at(x: usize, y: usize) -> Vec<String> {
+    let mut lines = vec![
+        "exec p1 : [robots/bender]".to_string(),
+        "exec p2 : [robots/terminator]".to_string(),
+    ];
+
+    for _ in 0..2 {
+        lines.extend(
+            [
+                "Anfield 4 3:",
+                "    0123",
+                "000 @...",
+                "001 ....",
+                "002 ...$",
+            ]
+            .map(str::to_string),
+        );
+        lines.extend(["Piece 2 2:", ".O", "OO"].map(str::to_string));
+        lines.push(format!("-> Answer (@): {x} {y}"));
+    }
+
+    lines.push("Player1 won!".to_string());
+    lines
+}
+
+proptest! {
+    #[test]
+    fn answers_off_the_board_never_panic(
+        x in prop_oneof![Just(usize::MAX), usize::MAX - 4.., any::<usize>()],
+        y in prop_oneof![Just(usize::MAX), usize::MAX - 4.., any::<usize>()],
+    ) {
+        let (state, _) = parse(&answered_at(x, y));
+        check_turns(&state);
+        prop_assert_eq!(state.turns[0].answer.answer, Some((x, y)));
+    }
+
+    #[test]
+    fn random_lines_never_panic(lines in proptest::collection::vec(".*", 0..40)) {
+        let (state, _) = parse(&lines);
+        check_turns(&state);
+    }
+
+    #[test]
+    fn mutated_fixtures_never_panic(
+        fixture in 0..6usize,
+        mutations in proptest::collection::vec((any::<usize>(), mutation()), 1..12),
+    ) {
+        let mut lines = fixture_lines().swap_remove(fixture);
+        for (at, mutation) in &mutations {
+            apply(&mut lines, *at, mutation);
+        }
+
+        let (state, _) = parse(&lines);
+        check_turns(&state);
+    }
+
+    #[test]
+    fn generated_games_parse_cleanly((lines, turns) in transcript()) {
+        let mut state = State::default();
+        let mut events = Vec::new();
+        for line in &lines {
+            events.push(state.parse_line(line, false));
+        }
+
+        prop_assert!(events.iter().all(Result::is_ok));
+        let events: Vec<Event> = events.into_iter().filter_map(Result::unwrap).collect();
+
+        let mut expected = vec![Event::Player(1), Event::Player(2)];
+        expected.extend((0..turns).map(Event::Turn));
+        expected.push(Event::Finished);
+        prop_assert_eq!(events, expected);
+        prop_assert_eq!(state.turns.len(), turns);
+    }
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+use super::{diff::MapDiff, map::Map, player::Player, turn::Turn, win::Win, State};
+
+/// Everything parsed from a game, in a form that can be saved and loaded again
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub player1: Player,
+    pub player2: Player,
+    pub turns: Vec<Turn>,
+    pub final_map: Option<Map>,
+    pub win: Win,
+}
+
+impl Replay {
+    /// Every board of the game in order, ending with the board after the last answer
+    pub fn boards(&self) -> Vec<&Map> {
+        self.turns
+            .iter()
+            .map(|t| &t.map)
+            .chain(self.final_map.as_ref())
+            .collect()
+    }
+}
+
+impl From<&State> for Replay {
+    fn from(state: &State) -> Self {
+        Replay {
+            player1: state.player1.clone(),
+            player2: state.player2.clone(),
+            turns: state.turns.clone(),
+            final_map: state.final_map(),
+            win: state.win.clone(),
+        }
+    }
+}
+
+impl From<Replay> for State {
+    /// The game as if it had been parsed, claims are worked out again from the boards
+    fn from(replay: Replay) -> Self {
+        let mut state = State {
+            player1: replay.player1,
+            player2: replay.player2,
+            win: replay.win,
+            last_map: replay.final_map,
+            ..Default::default()
+        };
+
+        for (i, turn) in replay.turns.into_iter().enumerate() {
+            let diff = state
+                .turns
+                .last()
+                .map(|previous| MapDiff::between(&previous.map, &turn.map));
+            state.claims.record(i, &turn.map, diff.as_ref());
+            state.turns.push(turn);
+        }
+
+        state
+    }
+}
@@ -0,0 +1,220 @@
+use std::{env, fmt::Write, fs, path::PathBuf};
+
+use super::{diff::MapDiff, map::MapTile, piece::PieceTile, win::GameOutcome, State};
+
+/// Engine transcripts and what parsing them should give, set UPDATE_GOLDEN=1 to rewrite
+/// the golden files after an intended change and review the diff
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+fn parse(name: &str) -> State {
+    let text = fs::read_to_string(fixture(&format!("{name}.txt"))).unwrap();
+    State::from_reader(text.as_bytes(), false).unwrap()
+}
+
+/// Everything the parser made of a game, in the form it is displayed
+fn snapshot(state: &State) -> String {
+    let mut out = String::new();
+
+    writeln!(out, "{}", state.player1).unwrap();
+    writeln!(out, "{}", state.player2).unwrap();
+
+    for (i, turn) in state.turns.iter().enumerate() {
+        writeln!(out, "== Turn {i}").unwrap();
+        write!(out, "{}", turn.map).unwrap();
+        write!(out, "{}", turn.piece).unwrap();
+        writeln!(out, "{}", turn.answer).unwrap();
+    }
+
+    writeln!(out, "== Final").unwrap();
+    match &state.last_map {
+        Some(map) => write!(out, "{map}").unwrap(),
+        None => writeln!(out, "No final map").unwrap(),
+    }
+
+    writeln!(out, "== Win").unwrap();
+    writeln!(out, "{}", state.win).unwrap();
+
+    out
+}
+
+fn check_golden(name: &str) -> State {
+    let state = parse(name);
+    let actual = snapshot(&state);
+    let path = fixture(&format!("{name}.golden"));
+
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, &actual).unwrap();
+        return state;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap();
+    assert!(
+        actual == expected,
+        "{name} doesn't match {}, rerun with UPDATE_GOLDEN=1 if the change is intended\n{actual}",
+        path.display()
+    );
+
+    state
+}
+
+#[test]
+fn win() {
+    let state = check_golden("win");
+
+    assert_eq!(state.turns.len(), 12);
+    assert_eq!(state.player1.path, "robots/bender");
+    assert_eq!(state.player2.path, "robots/terminator");
+    assert_eq!(state.win.outcome, Some(GameOutcome::Win { player: 1 }));
+    assert_eq!(state.win.seed, "7");
+}
+
+#[test]
+fn draw() {
+    let state = check_golden("draw");
+
+    assert_eq!(state.win.outcome, Some(GameOutcome::Draw));
+    assert_eq!(state.win.player1_score, state.win.player2_score);
+}
+
+#[test]
+fn stray_tie() {
+    let state = check_golden("stray_tie");
+
+    // robots talking about ties, wins and failures between their moves don't end the game
+    assert_eq!(state.turns.len(), 12);
+    assert_eq!(state.win.outcome, Some(GameOutcome::Win { player: 1 }));
+    assert_eq!((state.win.player1_score, state.win.player2_score), (12, 10));
+}
+
+#[test]
+fn invalid_answer() {
+    let state = check_golden("invalid_answer");
+
+    // a player with nowhere left to go still answers, the engine leaves the board as it was
+    let rejected: Vec<_> = state
+        .turns
+        .iter()
+        .filter(|turn| {
+            let (pos, tile) = (turn.answer.answer.unwrap(), turn.answer.tile().unwrap());
+            !turn.map.fits(&turn.piece, pos, tile)
+        })
+        .collect();
+    assert_eq!(rejected.len(), 2);
+    assert!(state.win.finished);
+}
+
+#[test]
+fn timeout() {
+    let state = check_golden("timeout");
+
+    // the winner line after it doesn't hide why player 2 lost
+    assert_eq!(state.win.outcome, Some(GameOutcome::Timeout { player: 2 }));
+    assert_eq!(state.turns[5].answer.answer, None);
+}
+
+#[test]
+fn big_map() {
+    let state = check_golden("big_map");
+
+    let map = state.last_map.as_ref().unwrap();
+    assert_eq!((map.width, map.heigth), (99, 100));
+    assert_eq!(map.tiles.len(), 100);
+    assert!(map.tiles.iter().all(|row| row.len() == 99));
+}
+
+#[test]
+fn tiny_map() {
+    let state = check_golden("tiny_map");
+
+    let map = state.last_map.as_ref().unwrap();
+    assert_eq!((map.width, map.heigth), (2, 2));
+    assert_eq!(map.count(MapTile::Player1), 1);
+    assert_eq!(map.count(MapTile::Player2), 1);
+    // neither player could place a piece, so neither won
+    assert_eq!(state.win.outcome, Some(GameOutcome::Draw));
+}
+
+#[test]
+fn aborted() {
+    let state = check_golden("aborted");
+
+    assert_eq!(state.win.outcome, Some(GameOutcome::Aborted));
+    // the half printed board at the end isn't a turn
+    assert_eq!(state.turns.len(), 7);
+}
+
+#[test]
+fn final_map_has_last_answer() {
+    let state = parse("win");
+
+    let last = state.turns.last().unwrap();
+    let (x, y) = last.answer.answer.unwrap();
+    let map = state.last_map.unwrap();
+
+    for (py, row) in last.piece.tiles.iter().enumerate() {
+        for (px, tile) in row.iter().enumerate() {
+            if *tile == PieceTile::Piece {
+                assert_ne!(map.tiles[y + py][x + px], MapTile::Empty);
+            }
+        }
+    }
+}
+
+#[test]
+fn pieces_match_their_header() {
+    for name in [
+        "win",
+        "draw",
+        "invalid_answer",
+        "timeout",
+        "big_map",
+        "tiny_map",
+    ] {
+        for (i, turn) in parse(name).turns.iter().enumerate() {
+            let piece = &turn.piece;
+
+            assert_eq!(piece.tiles.len(), piece.heigth, "{name} turn {i}");
+            assert!(
+                piece.tiles.iter().all(|row| row.len() == piece.width),
+                "{name} turn {i}"
+            );
+        }
+    }
+}
+
+/// The fixtures are written to the rules the engine plays by, every board is the one
+/// before with the answered piece added, or the same when the engine rejected the answer
+#[test]
+fn fixtures_follow_the_rules() {
+    for name in [
+        "win",
+        "draw",
+        "invalid_answer",
+        "timeout",
+        "big_map",
+        "tiny_map",
+        "aborted",
+        "stray_tie",
+    ] {
+        let state = parse(name);
+
+        for (i, turns) in state.turns.windows(2).enumerate() {
+            let (turn, next) = (&turns[0], &turns[1]);
+            let diff = MapDiff::between(&turn.map, &next.map);
+
+            let placed = match (turn.answer.answer, turn.answer.tile()) {
+                (Some(pos), Some(tile)) => turn.map.fits(&turn.piece, pos, tile),
+                _ => false,
+            };
+            if placed {
+                assert_eq!(diff.check(turn), Vec::new(), "{name} turn {i}");
+            } else {
+                assert!(diff.is_empty(), "{name} turn {i}");
+            }
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+/// A tile of a map or piece, written as the character the engine prints for it
+pub trait Tile: Sized {
+    fn try_from_char(c: char) -> Option<Self>;
+
+    fn to_char(&self) -> char;
+}
+
+/// Rows are saved as strings like the engine prints them, it keeps replays small
+pub fn serialize_rows<T: Tile, S: Serializer>(tiles: &[Vec<T>], s: S) -> Result<S::Ok, S::Error> {
+    s.collect_seq(
+        tiles
+            .iter()
+            .map(|row| row.iter().map(|t| t.to_char()).collect::<String>()),
+    )
+}
+
+pub fn deserialize_rows<'de, T: Tile, D: Deserializer<'de>>(d: D) -> Result<Vec<Vec<T>>, D::Error> {
+    Vec::<String>::deserialize(d)?
+        .iter()
+        .map(|row| {
+            row.chars()
+                .map(|c| {
+                    T::try_from_char(c)
+                        .ok_or_else(|| D::Error::custom(format!("invalid tile {c:?}")))
+                })
+                .collect()
+        })
+        .collect()
+}
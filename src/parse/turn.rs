@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+use super::{answer::Answer, map::Map, piece::Piece};
+
+/// One move of the game, the board as the engine printed it before the answer
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Turn {
+    pub map: Map,
+    pub piece: Piece,
+    pub answer: Answer,
+}
@@ -1,20 +1,45 @@
 use std::fmt::Display;
 
-use bevy::{ecs::schedule::ShouldRun, prelude::Res};
+use serde::{Deserialize, Serialize};
 
-use super::State;
-
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Win {
     pub seed: String,
     pub player1_score: usize,
     pub player2_score: usize,
-    pub winner: usize,
+    /// How the game ended, known once the engine has said so
+    pub outcome: Option<GameOutcome>,
 
     pub start: Option<usize>,
     pub finished: bool,
 }
 
+/// How a game ended, a player that timed out, crashed or was disqualified lost.
+/// Aborted games stopped before the engine printed a result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GameOutcome {
+    Win { player: usize },
+    Draw,
+    Timeout { player: usize },
+    Crash { player: usize },
+    Disqualified { player: usize },
+    Aborted,
+}
+
+impl Display for GameOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameOutcome::Win { player } => write!(f, "Player {player} won!"),
+            GameOutcome::Draw => write!(f, "Draw!"),
+            GameOutcome::Timeout { player } => write!(f, "Player {player} timed out"),
+            GameOutcome::Crash { player } => write!(f, "Player {player} crashed"),
+            GameOutcome::Disqualified { player } => write!(f, "Player {player} was disqualified"),
+            GameOutcome::Aborted => write!(f, "Game aborted"),
+        }
+    }
+}
+
 impl Display for Win {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Seed: {}", self.seed)?;
@@ -22,14 +47,9 @@ impl Display for Win {
         writeln!(f, "Player 1 score: {}", self.player1_score)?;
         writeln!(f, "Player 2 score: {}", self.player2_score)?;
 
-        write!(f, "Player {} won!", self.winner)
-    }
-}
-
-pub fn game_hasnt_finished(state: Res<State>) -> ShouldRun {
-    if state.win.finished {
-        ShouldRun::No
-    } else {
-        ShouldRun::Yes
+        match self.outcome {
+            Some(outcome) => write!(f, "{outcome}"),
+            None => write!(f, "No result"),
+        }
     }
 }
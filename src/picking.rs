@@ -0,0 +1,145 @@
+use bevy::{
+    prelude::{
+        Camera, Commands, ComputedVisibility, Entity, GlobalTransform, Input, MouseButton, Query,
+        Res, ResMut, Resource, With,
+    },
+    window::Windows,
+};
+use bevy_egui::{egui, EguiContext};
+
+use crate::{
+    board::Board,
+    draw::Panels,
+    games::{focus, Focused},
+    parse::{map::MapTile, State},
+    Args,
+};
+
+/// Tiles under the cursor and pinned with a click, with the game they are in and as (x, y)
+/// like the engine answers
+#[derive(Resource, Default, Debug)]
+pub struct Picking {
+    pub hovered: Option<(Entity, (usize, usize))>,
+    pub pinned: Option<(Entity, (usize, usize))>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn pick_tile(
+    mut commands: Commands,
+    windows: Res<Windows>,
+    buttons: Res<Input<MouseButton>>,
+    args: Res<Args>,
+    mut egui_context: ResMut<EguiContext>,
+    mut picking: ResMut<Picking>,
+
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    boards: Query<(Entity, &Board, &GlobalTransform, &ComputedVisibility)>,
+    focused: Query<Entity, With<Focused>>,
+) {
+    picking.hovered = None;
+
+    // the egui windows are drawn over the board, they get the pointer first
+    if egui_context.ctx_mut().wants_pointer_input() {
+        return;
+    }
+
+    let (Some(window), Some((camera, camera_transform))) = (
+        windows.get_primary(),
+        cameras.iter().find(|(camera, _)| camera.is_active),
+    ) else {
+        return;
+    };
+
+    let Some(ray) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
+    else {
+        return;
+    };
+
+    // every board picks in its own space, where tile (x, y) sits at (x, 0, y)
+    picking.hovered = boards
+        .iter()
+        .filter(|(_, _, _, visibility)| visibility.is_visible_in_hierarchy())
+        .find_map(|(entity, board, transform, _)| {
+            let inverse = transform.compute_matrix().inverse();
+            let origin = inverse.transform_point3(ray.origin);
+            let direction = inverse.transform_vector3(ray.direction);
+
+            Some((entity, board.pick(origin, direction, &args)?))
+        });
+
+    if let Some((game, _)) = picking.hovered {
+        if buttons.just_pressed(MouseButton::Left) {
+            picking.pinned = picking.hovered;
+
+            // clicking a board in the grid makes it the game the panels show
+            if !focused.contains(game) {
+                focus(&mut commands, focused.iter(), game);
+            }
+        }
+    }
+}
+
+pub fn draw_tile_info(
+    panels: Res<Panels>,
+    mut picking: ResMut<Picking>,
+    mut egui_context: ResMut<EguiContext>,
+    games: Query<(&State, &Board)>,
+) {
+    let ctx = egui_context.ctx_mut();
+
+    if let Some((game, pos)) = picking.hovered {
+        if let Ok((state, board)) = games.get(game) {
+            egui::show_tooltip_at_pointer(ctx, egui::Id::new("tile_tooltip"), |ui| {
+                tile_info(ui, pos, state, board)
+            });
+        }
+    }
+
+    if let Some((game, pos)) = picking.pinned.filter(|_| panels.shown) {
+        let Ok((state, board)) = games.get(game) else {
+            return;
+        };
+        let mut open = true;
+
+        egui::Window::new("Tile")
+            .open(&mut open)
+            .show(ctx, |ui| tile_info(ui, pos, state, board));
+
+        if !open {
+            picking.pinned = None;
+        }
+    }
+}
+
+fn tile_info(ui: &mut egui::Ui, (x, y): (usize, usize), state: &State, board: &Board) {
+    ui.label(format!("Tile: ({x}, {y})"));
+
+    let tile = board.map.tiles.get(y).and_then(|r| r.get(x)).copied();
+    ui.label(match tile.unwrap_or_default() {
+        MapTile::Player1 => format!("Owner: {}", state.player1),
+        MapTile::Player2 => format!("Owner: {}", state.player2),
+        _ => "Owner: nobody".to_string(),
+    });
+
+    // claims count boards, the answer given on the board before the claim placed the tile.
+    // Tiles claimed after the shown board aren't on it yet
+    match state
+        .claims
+        .get(x, y)
+        .filter(|claimed| *claimed <= board.turn)
+    {
+        Some(0) => {
+            ui.label("Claimed: starting tile");
+        }
+        Some(claimed) => {
+            ui.label(format!("Claimed on turn: {}", claimed - 1));
+
+            if let Some(turn) = state.turns.get(claimed - 1) {
+                ui.label(format!("Claimed by: {}", turn.answer));
+            }
+        }
+        None => {}
+    }
+}
@@ -0,0 +1,158 @@
+use bevy::{
+    prelude::{Component, Query, Res, ResMut, With},
+    time::Time,
+};
+use bevy_egui::{egui, EguiContext};
+
+use crate::{draw::Panels, games::Focused, parse::State, Args};
+
+/// Which board is shown and how fast it moves on, the parser keeps reading the engine
+/// regardless so nothing is lost while paused. Every game has its own
+#[derive(Component, Debug)]
+pub struct Playback {
+    /// Board being shown, counted like `State::board`
+    pub turn: usize,
+    pub paused: bool,
+    pub turns_per_second: f32,
+
+    /// Shows every board as soon as it is parsed
+    pub unlimited: bool,
+
+    /// Progress towards the next turn, in turns
+    elapsed: f32,
+}
+
+impl Playback {
+    pub fn new(args: &Args) -> Self {
+        Playback {
+            turn: 0,
+            paused: false,
+            turns_per_second: if args.speed > 0.0 { args.speed } else { 10.0 },
+            unlimited: args.speed <= 0.0,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Moves by the given number of turns and pauses, so stepping shows exactly one turn
+    pub fn step(&mut self, by: isize, board_count: usize) {
+        self.paused = true;
+        self.seek(self.turn.saturating_add_signed(by), board_count);
+    }
+
+    pub fn seek(&mut self, turn: usize, board_count: usize) {
+        self.turn = turn.min(board_count.saturating_sub(1));
+        self.elapsed = 0.0;
+    }
+
+    /// Goes to the newest board and keeps following the engine from there
+    pub fn jump_to_live(&mut self, board_count: usize) {
+        self.paused = false;
+        self.seek(board_count, board_count);
+    }
+
+    pub fn is_live(&self, board_count: usize) -> bool {
+        self.turn + 1 >= board_count
+    }
+}
+
+pub fn advance_playback(time: Res<Time>, mut games: Query<(&State, &mut Playback)>) {
+    for (state, mut playback) in games.iter_mut() {
+        let count = state.board_count();
+        if count == 0 || playback.paused {
+            continue;
+        }
+
+        let last = count - 1;
+        if playback.unlimited {
+            playback.turn = last;
+            continue;
+        }
+
+        playback.elapsed += time.delta_seconds() * playback.turns_per_second;
+        while playback.elapsed >= 1.0 && playback.turn < last {
+            playback.turn += 1;
+            playback.elapsed -= 1.0;
+        }
+
+        // time isn't saved up while waiting for the engine, new turns come in at the set speed
+        if playback.turn >= last {
+            playback.elapsed = 0.0;
+        }
+    }
+}
+
+pub fn draw_playback(
+    panels: Res<Panels>,
+    mut egui_context: ResMut<EguiContext>,
+    mut games: Query<(&State, &mut Playback), With<Focused>>,
+) {
+    if !panels.shown {
+        return;
+    }
+
+    let Ok((state, mut playback)) = games.get_single_mut() else {
+        return;
+    };
+
+    let count = state.board_count();
+
+    egui::Window::new("Playback").show(egui_context.ctx_mut(), |ui| {
+        if count == 0 {
+            ui.label("Waiting for the game to start");
+            return;
+        }
+
+        let last = count - 1;
+        ui.label(format!("Turn {}/{last}", playback.turn));
+        if !playback.is_live(count) {
+            ui.label(format!("{} turns behind", last - playback.turn));
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("⏮").on_hover_text("First turn").clicked() {
+                playback.paused = true;
+                playback.seek(0, count);
+            }
+            if ui.button("◀").on_hover_text("Previous turn").clicked() {
+                playback.step(-1, count);
+            }
+
+            let label = if playback.paused {
+                "▶ Play"
+            } else {
+                "⏸ Pause"
+            };
+            if ui.button(label).clicked() {
+                playback.paused = !playback.paused;
+            }
+
+            if ui.button("▶").on_hover_text("Next turn").clicked() {
+                playback.step(1, count);
+            }
+            if ui
+                .button("⏭ Live")
+                .on_hover_text("Jump to the newest turn")
+                .clicked()
+            {
+                playback.jump_to_live(count);
+            }
+        });
+
+        let mut turn = playback.turn;
+        if ui
+            .add(egui::Slider::new(&mut turn, 0..=last).text("turn"))
+            .changed()
+        {
+            playback.paused = true;
+            playback.seek(turn, count);
+        }
+
+        ui.add_enabled(
+            !playback.unlimited,
+            egui::Slider::new(&mut playback.turns_per_second, 0.5..=120.0)
+                .logarithmic(true)
+                .text("turns per second"),
+        );
+        ui.checkbox(&mut playback.unlimited, "As fast as possible");
+    });
+}
@@ -0,0 +1,248 @@
+use std::{
+    fs,
+    num::NonZeroU32,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::{
+    prelude::{
+        App, Assets, Camera, Camera3dBundle, Commands, DespawnRecursiveExt, Entity, EventReader,
+        GlobalTransform, Handle, Image, Plugin, Projection, Query, Res, ResMut, Resource, With,
+    },
+    render::{
+        camera::RenderTarget,
+        extract_resource::{ExtractResource, ExtractResourcePlugin},
+        render_asset::RenderAssets,
+        render_resource::{
+            BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d, ImageCopyBuffer,
+            ImageDataLayout, MapMode, TextureDescriptor, TextureDimension, TextureFormat,
+            TextureUsages,
+        },
+        renderer::{RenderDevice, RenderQueue},
+        RenderApp, RenderStage,
+    },
+    window::Windows,
+};
+use image::RgbaImage;
+
+use crate::{games::Focused, keys::Action, playback::Playback, Args};
+
+/// Saves the 3d view as a png, without the egui windows on top
+pub struct ScreenshotPlugin;
+
+impl Plugin for ScreenshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Screenshot>()
+            .add_plugin(ExtractResourcePlugin::<Screenshot>::default())
+            .add_system(take_screenshot);
+
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            // cleanup runs after the frame has been submitted, the image holds this frame's view
+            render_app.add_system_to_stage(RenderStage::Cleanup, copy_screenshot);
+        }
+    }
+}
+
+/// The screenshot being taken, a copy of the active camera renders into an image
+/// which the render world reads back once it has been drawn
+#[derive(Resource, ExtractResource, Clone, Default)]
+pub struct Screenshot(Option<Capture>);
+
+#[derive(Clone)]
+struct Capture {
+    image: Handle<Image>,
+    camera: Entity,
+    path: PathBuf,
+    frames: usize,
+    pixels: Arc<Mutex<Option<RgbaImage>>>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn take_screenshot(
+    mut commands: Commands,
+    mut actions: EventReader<Action>,
+    mut screenshot: ResMut<Screenshot>,
+    mut images: ResMut<Assets<Image>>,
+    windows: Res<Windows>,
+    args: Res<Args>,
+
+    cameras: Query<(&Camera, &GlobalTransform, &Projection)>,
+    playbacks: Query<&Playback, With<Focused>>,
+) {
+    let requested = actions.iter().any(|action| *action == Action::Screenshot);
+
+    if let Some(capture) = &mut screenshot.0 {
+        let Some(pixels) = capture.pixels.lock().unwrap().take() else {
+            capture.frames += 1;
+            return;
+        };
+
+        match pixels.save(&capture.path) {
+            Ok(()) => eprintln!("Saved screenshot to {}", capture.path.display()),
+            Err(err) => eprintln!(
+                "Couldn't save screenshot to {}: {err}",
+                capture.path.display()
+            ),
+        }
+
+        commands.entity(capture.camera).despawn_recursive();
+        images.remove(&capture.image);
+        screenshot.0 = None;
+        return;
+    }
+
+    if !requested {
+        return;
+    }
+
+    let (Some(window), Some((camera, transform, projection))) = (
+        windows.get_primary(),
+        cameras.iter().find(|(camera, _, _)| camera.is_active),
+    ) else {
+        return;
+    };
+
+    let size = Extent3d {
+        width: window.physical_width(),
+        height: window.physical_height(),
+        depth_or_array_layers: 1,
+    };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: Some("screenshot"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            usage: TextureUsages::RENDER_ATTACHMENT
+                | TextureUsages::COPY_SRC
+                | TextureUsages::COPY_DST
+                | TextureUsages::TEXTURE_BINDING,
+        },
+        ..Default::default()
+    };
+    image.resize(size);
+    let image = images.add(image);
+
+    // same view as whichever camera is active, fly or top down
+    let camera = commands
+        .spawn(Camera3dBundle {
+            camera: Camera {
+                target: RenderTarget::Image(image.clone()),
+                priority: camera.priority - 1,
+                ..Default::default()
+            },
+            transform: transform.compute_transform(),
+            projection: projection.clone(),
+            ..Default::default()
+        })
+        .id();
+
+    if let Err(err) = fs::create_dir_all(&args.screenshot_dir) {
+        eprintln!("Couldn't create {}: {err}", args.screenshot_dir.display());
+    }
+
+    screenshot.0 = Some(Capture {
+        image,
+        camera,
+        path: args.screenshot_dir.join(format!(
+            "screenshot-{}-turn{}.png",
+            timestamp(SystemTime::now()),
+            playbacks.get_single().map_or(0, |playback| playback.turn)
+        )),
+        frames: 0,
+        pixels: Arc::default(),
+    });
+}
+
+fn copy_screenshot(
+    screenshot: Res<Screenshot>,
+    images: Res<RenderAssets<Image>>,
+    device: Res<RenderDevice>,
+    queue: Res<RenderQueue>,
+) {
+    // the camera is drawn for the first time in the frame it was spawned in
+    let Some(capture) = screenshot.0.as_ref().filter(|capture| capture.frames > 0) else {
+        return;
+    };
+    let Some(gpu_image) = images.get(&capture.image) else {
+        return;
+    };
+
+    let (width, height) = (gpu_image.size.x as u32, gpu_image.size.y as u32);
+    let row = width as usize * 4;
+    // buffer rows have to be aligned, the padding is cut off when reading them
+    let padded_row = RenderDevice::align_copy_bytes_per_row(row);
+
+    let buffer = device.create_buffer(&BufferDescriptor {
+        label: Some("screenshot"),
+        size: (padded_row * height as usize) as u64,
+        usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("screenshot"),
+    });
+    encoder.copy_texture_to_buffer(
+        gpu_image.texture.as_image_copy(),
+        ImageCopyBuffer {
+            buffer: &buffer,
+            layout: ImageDataLayout {
+                offset: 0,
+                bytes_per_row: NonZeroU32::new(padded_row as u32),
+                rows_per_image: None,
+            },
+        },
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+    );
+    queue.submit([encoder.finish()]);
+
+    let slice = buffer.slice(..);
+    device.map_buffer(&slice, MapMode::Read, |_| {});
+    device.poll(wgpu::Maintain::Wait);
+
+    let pixels = slice
+        .get_mapped_range()
+        .chunks(padded_row)
+        .flat_map(|padded| &padded[..row])
+        .copied()
+        .collect();
+    buffer.unmap();
+
+    *capture.pixels.lock().unwrap() = RgbaImage::from_raw(width, height, pixels);
+}
+
+/// Utc time as 20230114-153012, sorts the same way as the screenshots were taken
+fn timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (days, rest) = (secs / 86400, secs % 86400);
+
+    // days since 1970 to a date, from http://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}{month:02}{day:02}-{:02}{:02}{:02}",
+        rest / 3600,
+        rest / 60 % 60,
+        rest % 60
+    )
+}
@@ -1,29 +1,142 @@
-use bevy::prelude::{
-    shape, Assets, Color, Commands, Mesh, PbrBundle, ResMut, StandardMaterial, Transform,
+use std::{fs, mem, path::Path};
+
+use bevy::prelude::{Assets, Commands, Res, ResMut, Resource, StandardMaterial};
+
+use crate::{
+    compare::Comparison,
+    export,
+    games::{spawn_game, Focused, Game, GameView},
+    parse::{listen::Listener, map::Map, turn::Turn, Lines, State},
+    Args, Command,
 };
-use clap::Parser;
 
-use crate::{draw::MapBlock, Args};
+/// Games and sockets opened before the window, so bad paths are reported instead of panicking
+#[derive(Resource, Default)]
+pub struct Startup {
+    games: Vec<(String, State)>,
+    listener: Option<Listener>,
+}
+
+impl Startup {
+    pub fn load(args: &Args) -> Result<Self, String> {
+        let mut games = Vec::new();
+
+        if let Some(Command::Compare(compare)) = &args.command {
+            for path in [&compare.before, &compare.after] {
+                games.push((path.display().to_string(), load_game(path, args)?));
+            }
+        }
+
+        if let Some(path) = &args.map {
+            let map = fs::read_to_string(path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| text.parse::<Map>())
+                .map_err(|e| format!("Couldn't load map {}: {e}", path.display()))?;
+
+            // a lone map is shown as a game that never got past its first board
+            let mut state = State::default();
+            state.claims.record(0, &map, None);
+            state.turns.push(Turn {
+                map,
+                ..Default::default()
+            });
+
+            games.push((path.display().to_string(), state));
+        }
+
+        for path in &args.file {
+            games.push((path.display().to_string(), load_game(path, args)?));
+        }
+
+        let listener = if args.listen.is_empty() {
+            None
+        } else {
+            Some(Listener::bind(&args.listen).map_err(|e| format!("Couldn't listen: {e}"))?)
+        };
+
+        Ok(Self { games, listener })
+    }
+}
 
 pub fn setup(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
+    args: Res<Args>,
+    mut startup: ResMut<Startup>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    mut args: ResMut<Args>,
 ) {
-    *args = Args::parse();
-
-    for x in 0..100 {
-        for y in 0..100 {
-            commands.spawn((
-                PbrBundle {
-                    mesh: meshes.add(Mesh::from(shape::Cube { size: 1.0 })),
-                    material: materials.add(Color::rgba(0.0, 0.0, 0.0, 0.0).into()),
-                    transform: Transform::from_xyz(x as f32, 0.0, y as f32),
-                    ..Default::default()
-                },
-                MapBlock { x, y },
-            ));
+    // games that are there from the start, sockets add theirs as the connections come in
+    let mut games: Vec<_> = mem::take(&mut startup.games)
+        .into_iter()
+        .map(|(name, state)| (name, state, None))
+        .collect();
+
+    if let Some(listener) = startup.listener.take() {
+        commands.insert_resource(listener);
+    } else if games.is_empty() {
+        games.push(("stdin".to_string(), State::default(), Some(Lines::stdin())));
+    }
+
+    // compared games come first, side by side in the grid
+    let comparison = matches!(args.command, Some(Command::Compare(_)))
+        .then(|| (games[0].1.clone(), games[1].1.clone()));
+
+    let mut entities = Vec::new();
+    for (index, (name, state, lines)) in games.into_iter().enumerate() {
+        let game = Game { name, index };
+        entities.push(spawn_game(
+            &mut commands,
+            &mut materials,
+            game,
+            state,
+            &args,
+            lines,
+        ));
+    }
+
+    if let Some(first) = entities.first() {
+        commands.entity(*first).insert(Focused);
+    }
+
+    if let Some((before, after)) = comparison {
+        let games = [entities[0], entities[1]];
+        commands.insert_resource(Comparison::new(games, &before, &after));
+        commands.insert_resource(GameView::Grid);
+    }
+}
+
+/// A finished game from engine output or a json replay
+fn load_game(path: &Path, args: &Args) -> Result<State, String> {
+    let replay = export::load(Some(path), args.debug)
+        .map_err(|e| format!("Couldn't load game {}: {e}", path.display()))?;
+
+    Ok(State::from(replay))
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"))
+    }
+
+    #[test]
+    fn bad_paths_are_errors() {
+        for flag in ["--map", "--file"] {
+            let args = Args::parse_from(["filler", flag, "does/not/exist.txt"]);
+            let err = Startup::load(&args).err().unwrap();
+            assert!(err.contains("does/not/exist.txt"), "{err}");
         }
     }
+
+    #[test]
+    fn games_load_before_the_window() {
+        let args = Args::parse_from(["filler", "--file", &fixture("win.txt")]);
+        let startup = Startup::load(&args).unwrap();
+
+        assert_eq!(startup.games.len(), 1);
+        assert!(startup.listener.is_none());
+    }
 }